@@ -1,4 +1,4 @@
-use crate::helpers::{is_writable, LOG_TARGET};
+use crate::helpers::{is_readable, is_writable, LOG_TARGET};
 use crate::items::{CacheItem, ProjectsState, RunTargetState, WorkspaceState};
 use crate::runfiles::CacheRunfile;
 use moon_config::constants::CONFIG_DIRNAME;
@@ -9,6 +9,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::path::{Path, PathBuf};
 
+#[derive(Clone)]
 pub struct CacheEngine {
     /// The `.moon/cache` directory relative to workspace root.
     /// Contains cached items pertaining to runs and processes.
@@ -53,12 +54,8 @@ impl CacheEngine {
         &self,
         target_id: &str,
     ) -> Result<CacheItem<RunTargetState>, MoonError> {
-        let path: PathBuf = [&target_id.replace(':', "/"), "lastRunState.json"]
-            .iter()
-            .collect();
-
         CacheItem::load(
-            self.runs_dir.join(path),
+            self.get_run_target_state_path(target_id),
             RunTargetState {
                 target: String::from(target_id),
                 ..RunTargetState::default()
@@ -68,6 +65,27 @@ impl CacheEngine {
         .await
     }
 
+    /// Synchronously read the last run state of a target, for contexts that
+    /// can not await, like the dependency graph. Does not create the cache file.
+    pub fn read_run_target_state(&self, target_id: &str) -> Result<RunTargetState, MoonError> {
+        let path = self.get_run_target_state_path(target_id);
+
+        if is_readable() && path.exists() {
+            trace!(
+                target: LOG_TARGET,
+                "Reading last run state for {}",
+                color::target(target_id)
+            );
+
+            return fs::read_json_sync(&path);
+        }
+
+        Ok(RunTargetState {
+            target: String::from(target_id),
+            ..RunTargetState::default()
+        })
+    }
+
     pub async fn cache_projects_state(&self) -> Result<CacheItem<ProjectsState>, MoonError> {
         CacheItem::load(
             self.dir.join("projectsState.json"),
@@ -130,6 +148,29 @@ impl CacheEngine {
         Ok(())
     }
 
+    /// Hard link previously archived task outputs back into the destination root.
+    /// Files that already exist are left untouched.
+    pub async fn hydrate_task_outputs(
+        &self,
+        hash: &str,
+        dest_root: &Path,
+    ) -> Result<(), MoonError> {
+        let source_root = self.outputs_dir.join(hash);
+
+        if is_readable() && source_root.exists() {
+            trace!(
+                target: LOG_TARGET,
+                "Hydrating outputs from {} to {}",
+                color::path(&source_root),
+                color::path(dest_root)
+            );
+
+            fs::link_dir(&source_root, &source_root, dest_root).await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn link_task_output_to_out(
         &self,
         hash: &str,
@@ -158,6 +199,14 @@ impl CacheEngine {
         Ok(())
     }
 
+    fn get_run_target_state_path(&self, target_id: &str) -> PathBuf {
+        let path: PathBuf = [&target_id.replace(':', "/"), "lastRunState.json"]
+            .iter()
+            .collect();
+
+        self.runs_dir.join(path)
+    }
+
     pub async fn save_hash<T>(&self, hash: &str, hasher: &T) -> Result<(), MoonError>
     where
         T: ?Sized + Serialize,
@@ -180,6 +229,7 @@ mod tests {
     use crate::helpers::{run_with_env, to_millis};
     use assert_fs::prelude::*;
     use serial_test::serial;
+    use std::collections::BTreeMap;
     use std::fs;
 
     mod create {
@@ -490,6 +540,51 @@ mod tests {
         }
     }
 
+    mod read_run_target_state {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_default_if_missing() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            assert_eq!(
+                cache.read_run_target_state("foo:bar").unwrap(),
+                RunTargetState {
+                    target: String::from("foo:bar"),
+                    ..RunTargetState::default()
+                }
+            );
+            assert!(!dir
+                .path()
+                .join(".moon/cache/runs/foo/bar/lastRunState.json")
+                .exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn reads_input_env_vars() {
+            let dir = assert_fs::TempDir::new().unwrap();
+
+            dir.child(".moon/cache/runs/foo/bar/lastRunState.json")
+                .write_str(r#"{"exitCode":0,"hash":"","inputEnvVars":{"NODE_ENV":"test"},"lastRunTime":0,"stderr":"","stdout":"","target":"foo:bar"}"#)
+                .unwrap();
+
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let state = cache.read_run_target_state("foo:bar").unwrap();
+
+            assert_eq!(
+                state.input_env_vars,
+                BTreeMap::from([("NODE_ENV".to_owned(), "test".to_owned())])
+            );
+
+            dir.close().unwrap();
+        }
+    }
+
     mod cache_workspace_state {
         use super::*;
 
@@ -727,6 +822,43 @@ mod tests {
         }
     }
 
+    mod hydrate_task_outputs {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn restores_archived_files() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child("project/dist/index.js").write_str("").unwrap();
+            dir.child("project/dist/index.js.map")
+                .write_str("")
+                .unwrap();
+
+            cache
+                .link_task_output_to_out(
+                    "abc123",
+                    dir.path(),
+                    &dir.path().join("project/dist/index.js"),
+                )
+                .await
+                .unwrap();
+
+            fs::remove_dir_all(dir.path().join("project/dist")).unwrap();
+
+            cache
+                .hydrate_task_outputs("abc123", dir.path())
+                .await
+                .unwrap();
+
+            assert!(dir.path().join("project/dist/index.js").exists());
+            assert!(!dir.path().join("project/dist/index.js.map").exists());
+
+            dir.close().unwrap();
+        }
+    }
+
     mod save_hash {
         use super::*;
         use serde::Deserialize;
@@ -4,7 +4,7 @@ use moon_logger::{color, trace};
 use moon_utils::fs;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
@@ -91,6 +91,10 @@ pub struct RunTargetState {
 
     pub hash: String,
 
+    /// Values of the task's input env vars when it last ran.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub input_env_vars: BTreeMap<String, String>,
+
     pub last_run_time: u128,
 
     pub stderr: String,
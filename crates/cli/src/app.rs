@@ -5,8 +5,8 @@ use std::path::PathBuf;
 use crate::commands::bin::BinTools;
 use crate::commands::init::{InheritProjectsAs, PackageManager};
 use crate::commands::run::RunStatus;
-use crate::enums::{CacheMode, LogLevel};
-use clap::{Parser, Subcommand};
+use crate::enums::{CacheMode, LogLevel, ProjectLanguage, ProjectType};
+use clap::{Args, Parser, Subcommand};
 use moon_project::TargetID;
 use moon_terminal::output::label_moon;
 
@@ -15,6 +15,107 @@ pub const BIN_NAME: &str = if cfg!(windows) { "moon.exe" } else { "moon" };
 const HEADING_AFFECTED: &str = "Affected by changes";
 const HEADING_PARALLELISM: &str = "Parallelism and distribution";
 
+#[derive(Args, Debug)]
+pub struct QueryFilterArgs {
+    #[clap(long, help = "Filter projects whose ID matches a glob pattern")]
+    pub id: Option<String>,
+
+    #[clap(arg_enum, long, help = "Filter projects of the provided language")]
+    pub language: Option<ProjectLanguage>,
+
+    #[clap(
+        long,
+        help = "Filter projects whose source path matches a glob pattern"
+    )]
+    pub source: Option<String>,
+
+    #[clap(
+        long = "tag",
+        help = "Filter projects with the provided tag (can be repeated)"
+    )]
+    pub tags: Vec<String>,
+
+    #[clap(arg_enum, long = "type", help = "Filter projects of the provided type")]
+    pub type_of: Option<ProjectType>,
+
+    // Affected
+    #[clap(
+        long,
+        help = "Only include results affected by changed files",
+        help_heading = HEADING_AFFECTED
+    )]
+    pub affected: bool,
+
+    #[clap(
+        arg_enum,
+        long,
+        help = "Filter affected files based on a change status",
+        help_heading = HEADING_AFFECTED,
+        default_value_t
+    )]
+    pub status: RunStatus,
+
+    #[clap(
+        long,
+        help = "Determine affected against upstream by comparing against a base revision",
+        help_heading = HEADING_AFFECTED
+    )]
+    pub upstream: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum QueryCommands {
+    // moon query projects
+    #[clap(
+        name = "projects",
+        about = "Query for projects that match the filters."
+    )]
+    Projects {
+        #[clap(flatten)]
+        filters: QueryFilterArgs,
+
+        #[clap(long, help = "Print in JSON format")]
+        json: bool,
+    },
+
+    // moon query tasks
+    #[clap(
+        name = "tasks",
+        about = "Query for tasks within projects that match the filters."
+    )]
+    Tasks {
+        #[clap(flatten)]
+        filters: QueryFilterArgs,
+
+        #[clap(long, help = "Print in JSON format")]
+        json: bool,
+    },
+
+    // moon query touched-files
+    #[clap(
+        name = "touched-files",
+        about = "Query for files that have been touched in the VCS."
+    )]
+    TouchedFiles {
+        #[clap(
+            arg_enum,
+            long,
+            help = "Filter files based on a change status",
+            default_value_t
+        )]
+        status: RunStatus,
+
+        #[clap(
+            long,
+            help = "Compare against upstream by comparing against a base revision"
+        )]
+        upstream: bool,
+
+        #[clap(long, help = "Print in JSON format")]
+        json: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     // ENVIRONMENT
@@ -79,6 +180,23 @@ pub enum Commands {
 
     // PROJECTS
 
+    // moon query <command>
+    #[clap(
+        name = "query",
+        about = "Query information about projects, tasks, and touched files."
+    )]
+    Query {
+        #[clap(subcommand)]
+        command: QueryCommands,
+    },
+
+    // moon check
+    #[clap(
+        name = "check",
+        about = "Check that all projects satisfy the workspace constraints."
+    )]
+    Check,
+
     // moon project <id>
     #[clap(
         name = "project",
@@ -95,12 +213,64 @@ pub enum Commands {
     // moon project-graph [id]
     #[clap(
         name = "project-graph",
-        about = "Display a graph of projects in DOT format.",
+        about = "Display a graph of projects in DOT format, or explore it interactively.",
         alias = "graph"
     )]
     ProjectGraph {
         #[clap(help = "ID of project to *only* graph")]
         id: Option<String>,
+
+        #[clap(long, help = "Serve an interactive graph explorer on a local server")]
+        serve: bool,
+
+        #[clap(long, help = "Host to bind the server to", default_value = "127.0.0.1")]
+        host: String,
+
+        #[clap(long, help = "Port to bind the server to", default_value_t = 8081)]
+        port: u16,
+    },
+
+    // moon dep-graph [...targets]
+    #[clap(
+        name = "dep-graph",
+        about = "Display a dependency graph of actions in DOT or JSON format."
+    )]
+    DepGraph {
+        #[clap(help = "List of targets (project:task) to graph, otherwise graphs all tasks")]
+        targets: Vec<TargetID>,
+
+        #[clap(
+            long,
+            help = "Include dependents of the same task, as well as dependencies"
+        )]
+        dependents: bool,
+
+        #[clap(long, help = "Print in JSON format")]
+        json: bool,
+
+        // Affected
+        #[clap(
+            long,
+            help = "Mark targets as ran, cached, or skipped based on changed files",
+            help_heading = HEADING_AFFECTED
+        )]
+        affected: bool,
+
+        #[clap(
+            arg_enum,
+            long,
+            help = "Filter affected files based on a change status",
+            help_heading = HEADING_AFFECTED,
+            default_value_t
+        )]
+        status: RunStatus,
+
+        #[clap(
+            long,
+            help = "Determine affected against upstream by comparing against a base revision",
+            help_heading = HEADING_AFFECTED
+        )]
+        upstream: bool,
     },
 
     // JOBS
@@ -118,6 +288,12 @@ pub enum Commands {
         #[clap(long, help = "Current branch, commit, or revision to compare with")]
         head: Option<String>,
 
+        #[clap(
+            long = "tag",
+            help = "Only run targets in projects with the provided tag (can be repeated)"
+        )]
+        tags: Vec<String>,
+
         #[clap(long, help = "Index of the current job", help_heading = HEADING_PARALLELISM)]
         job: Option<usize>,
 
@@ -128,11 +304,11 @@ pub enum Commands {
     // moon run [...targets]
     #[clap(
         name = "run",
-        about = "Run a project task and all its dependent tasks."
+        about = "Run one or many project tasks and all their dependent tasks."
     )]
     Run {
-        #[clap(help = "Target (project:task) to run")]
-        target: TargetID,
+        #[clap(help = "List of targets (project:task) to run", required = true)]
+        targets: Vec<TargetID>,
 
         #[clap(
             long,
@@ -143,7 +319,7 @@ pub enum Commands {
         // Affected
         #[clap(
             long,
-            help = "Only run target if affected by changed files",
+            help = "Only run targets if affected by changed files",
             help_heading = HEADING_AFFECTED
         )]
         affected: bool,
@@ -0,0 +1,55 @@
+use moon_config::ConstraintsConfig;
+use moon_logger::color;
+use moon_project::ProjectError;
+use moon_workspace::Workspace;
+
+pub async fn check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut workspace = Workspace::load().await?;
+    let constraints = workspace.config.constraints.clone();
+
+    // Disable enforcement while loading, so that all violations
+    // can be collected and reported at once
+    workspace
+        .projects
+        .set_constraints(ConstraintsConfig::default());
+
+    // Aliases are resolved while loading, so report every collision upfront
+    let collisions = workspace.projects.find_alias_collisions()?;
+
+    if !collisions.is_empty() {
+        let messages = collisions
+            .iter()
+            .map(|error| format!("  {}", error))
+            .collect::<Vec<String>>();
+
+        return Err(Box::new(ProjectError::AliasCollisions(
+            collisions.len(),
+            messages.join("\n"),
+        )));
+    }
+
+    for pid in workspace.projects.ids() {
+        workspace.projects.load(&pid)?;
+    }
+
+    let violations = workspace.projects.find_constraint_violations(&constraints);
+
+    if !violations.is_empty() {
+        let messages = violations
+            .iter()
+            .map(|error| format!("  {}", error))
+            .collect::<Vec<String>>();
+
+        return Err(Box::new(ProjectError::ConstraintViolations(
+            violations.len(),
+            messages.join("\n"),
+        )));
+    }
+
+    println!(
+        "{}",
+        color::success("All projects satisfy the workspace constraints")
+    );
+
+    Ok(())
+}
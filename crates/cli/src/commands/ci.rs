@@ -83,6 +83,7 @@ async fn gather_touched_files(
 fn gather_runnable_targets(
     workspace: &Workspace,
     touched_files: &TouchedFilePaths,
+    options: &CiOptions,
 ) -> Result<TargetList, WorkspaceError> {
     print_header("Gathering runnable targets");
 
@@ -99,6 +100,17 @@ fn gather_runnable_targets(
     for project_id in workspace.projects.ids() {
         let project = workspace.projects.load(&project_id)?;
 
+        // Only include projects that match at least 1 tag, when filtering
+        if !options.tags.is_empty() && !options.tags.iter().any(|tag| project.has_tag(tag)) {
+            debug!(
+                target: TARGET,
+                "Not running project {} because it does not match the tags filter",
+                color::id(&project_id),
+            );
+
+            continue;
+        }
+
         for (task_id, task) in &project.tasks {
             let target = Target::new(&project_id, task_id)?;
 
@@ -187,12 +199,13 @@ pub struct CiOptions {
     pub head: Option<String>,
     pub job: Option<usize>,
     pub job_total: Option<usize>,
+    pub tags: Vec<String>,
 }
 
 pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
     let touched_files = gather_touched_files(&workspace, &options).await?;
-    let targets = gather_runnable_targets(&workspace, &touched_files)?;
+    let targets = gather_runnable_targets(&workspace, &touched_files, &options)?;
 
     if targets.is_empty() {
         return Ok(());
@@ -0,0 +1,99 @@
+use crate::commands::run::{get_touched_files, RunStatus};
+use itertools::Itertools;
+use moon_project::{Target, TargetID};
+use moon_workspace::{is_target_cached, DepGraph, Node, NodeStatus, Workspace};
+use std::collections::HashMap;
+
+pub struct DepGraphOptions {
+    pub affected: bool,
+    pub dependents: bool,
+    pub json: bool,
+    pub status: RunStatus,
+    pub upstream: bool,
+}
+
+pub async fn dep_graph(
+    target_ids: &[TargetID],
+    options: DepGraphOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let mut targets = vec![];
+
+    // Graph every task in every project when no targets are provided
+    if target_ids.is_empty() {
+        for project_id in workspace.projects.ids() {
+            let project = workspace.projects.load(&project_id)?;
+
+            for task_id in project.tasks.keys().sorted() {
+                targets.push(Target::new(&project_id, task_id)?);
+            }
+        }
+    } else {
+        for target_id in target_ids {
+            targets.push(Target::parse(target_id)?);
+        }
+    }
+
+    // Generate the same dependency graph that `moon run` would
+    let mut dep_graph = DepGraph::default();
+
+    for target in &targets {
+        dep_graph.run_target(target, &workspace.projects, None)?;
+    }
+
+    if options.dependents {
+        for target in &targets {
+            dep_graph.run_target_dependents(target, &workspace.projects)?;
+        }
+    }
+
+    // Determine what would run, be cached, or be skipped based on touched files
+    let mut statuses = HashMap::new();
+
+    if options.affected {
+        let touched_files =
+            get_touched_files(&workspace, &options.status, options.upstream).await?;
+        let mut affected_graph = DepGraph::default();
+
+        for target in &targets {
+            affected_graph.run_target(target, &workspace.projects, Some(&touched_files))?;
+        }
+
+        if options.dependents {
+            for target in &targets {
+                affected_graph.run_target_dependents(target, &workspace.projects)?;
+            }
+        }
+
+        for index in dep_graph.graph.node_indices() {
+            let status = match dep_graph.get_node_from_index(index).unwrap() {
+                Node::RunTarget(target_id) => {
+                    if !affected_graph.has_target(target_id) {
+                        NodeStatus::Skipped
+                    } else if is_target_cached(
+                        &workspace,
+                        target_id,
+                        dep_graph.target_overrides.get(target_id),
+                    )
+                    .await?
+                    {
+                        NodeStatus::Cached
+                    } else {
+                        NodeStatus::Run
+                    }
+                }
+                _ => NodeStatus::Run,
+            };
+
+            statuses.insert(index, status);
+        }
+    }
+
+    if options.json {
+        println!("{}", dep_graph.to_json(&statuses)?);
+    } else {
+        println!("{}", dep_graph.to_dot_with_statuses(&statuses));
+    }
+
+    Ok(())
+}
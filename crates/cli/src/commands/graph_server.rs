@@ -0,0 +1,395 @@
+use moon_logger::color;
+use moon_project::{Target, TargetID};
+use moon_workspace::{DepGraph, Workspace};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+type Response = (&'static str, &'static str, String);
+
+const CONTENT_HTML: &str = "text/html; charset=utf-8";
+const CONTENT_JSON: &str = "application/json";
+
+const MAX_REQUEST_HEAD_SIZE: usize = 16384;
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    DepGraph(TargetID),
+    Page,
+    Path(String, String),
+    Project(String),
+    Projects,
+}
+
+fn load_page_template() -> &'static str {
+    include_str!("../../templates/graph.html")
+}
+
+/// Decode percent-encoded characters in a URL component, like `%3A` for `:`.
+/// A `+` is only a space within query strings, so is left as-is here.
+fn decode_component(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = vec![];
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or_default();
+
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        index += 3;
+                        continue;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        };
+
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn decode_query_component(value: &str) -> String {
+    decode_component(&value.replace('+', " "))
+}
+
+fn parse_url(url: &str) -> (String, HashMap<String, String>) {
+    let mut query = HashMap::new();
+    let (path, search) = url.split_once('?').unwrap_or((url, ""));
+
+    for pair in search.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        query.insert(decode_query_component(key), decode_query_component(value));
+    }
+
+    (decode_component(path), query)
+}
+
+fn json_response(value: serde_json::Value) -> Response {
+    ("200 OK", CONTENT_JSON, value.to_string())
+}
+
+fn error_response(status: &'static str, message: &str) -> Response {
+    (
+        status,
+        CONTENT_JSON,
+        json!({ "error": message }).to_string(),
+    )
+}
+
+fn list_projects(workspace: &Workspace) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut projects = vec![];
+
+    for id in workspace.projects.ids() {
+        let project = workspace.projects.load(&id)?;
+
+        projects.push(json!({
+            "id": project.id,
+            "source": project.source,
+            "dependencies": workspace.projects.get_dependencies_of(&project)?,
+            "dependents": workspace.projects.get_dependents_of(&project)?,
+        }));
+    }
+
+    Ok(json!({ "projects": projects }))
+}
+
+fn show_project(
+    workspace: &Workspace,
+    id: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let project = workspace.projects.load(id)?;
+    let dependencies = workspace.projects.get_dependencies_of(&project)?;
+    let dependents = workspace.projects.get_dependents_of(&project)?;
+
+    Ok(json!({
+        "project": project,
+        "dependencies": dependencies,
+        "dependents": dependents,
+    }))
+}
+
+fn find_path(
+    workspace: &Workspace,
+    from: &str,
+    to: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    // Dependencies may flow in either direction between the 2 projects
+    let path = match workspace.projects.get_path_between(from, to)? {
+        Some(path) => Some(path),
+        None => workspace.projects.get_path_between(to, from)?,
+    };
+
+    Ok(json!({ "path": path }))
+}
+
+fn build_dep_graph(
+    workspace: &Workspace,
+    target_id: &TargetID,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut dep_graph = DepGraph::default();
+
+    dep_graph.run_target(&Target::parse(target_id)?, &workspace.projects, None)?;
+
+    Ok(serde_json::from_str(&dep_graph.to_json(&HashMap::new())?)?)
+}
+
+fn route_request(method: &str, url: &str) -> Result<Route, Response> {
+    if method != "GET" {
+        return Err(error_response(
+            "405 Method Not Allowed",
+            "Only GET requests are supported.",
+        ));
+    }
+
+    let (path, mut query) = parse_url(url);
+
+    Ok(match path.as_str() {
+        "/" | "/index.html" => Route::Page,
+        "/api/projects" => Route::Projects,
+        "/api/path" => match (query.remove("from"), query.remove("to")) {
+            (Some(from), Some(to)) => Route::Path(from, to),
+            _ => {
+                return Err(error_response(
+                    "400 Bad Request",
+                    "Both `from` and `to` are required.",
+                ));
+            }
+        },
+        "/api/dep-graph" => match query.remove("target") {
+            Some(target) => Route::DepGraph(target),
+            None => return Err(error_response("400 Bad Request", "A `target` is required.")),
+        },
+        other => match other.strip_prefix("/api/projects/") {
+            Some(id) => Route::Project(id.to_owned()),
+            None => return Err(error_response("404 Not Found", "Not found.")),
+        },
+    })
+}
+
+fn handle_request(workspace: &Workspace, method: &str, url: &str) -> Response {
+    let route = match route_request(method, url) {
+        Ok(route) => route,
+        Err(response) => return response,
+    };
+
+    let result = match route {
+        Route::DepGraph(target) => build_dep_graph(workspace, &target),
+        Route::Page => {
+            return ("200 OK", CONTENT_HTML, load_page_template().to_owned());
+        }
+        Route::Path(from, to) => find_path(workspace, &from, &to),
+        Route::Project(id) => show_project(workspace, &id),
+        Route::Projects => list_projects(workspace),
+    };
+
+    match result {
+        Ok(value) => json_response(value),
+        Err(error) => error_response("400 Bad Request", &error.to_string()),
+    }
+}
+
+/// Read the request line and headers, which may arrive across multiple reads.
+/// Bodies are not supported, so anything after the headers is ignored.
+async fn read_request_head<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<String> {
+    let mut head = vec![];
+    let mut buffer = [0; 1024];
+
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let size = reader.read(&mut buffer).await?;
+
+        if size == 0 {
+            break;
+        }
+
+        head.extend_from_slice(&buffer[..size]);
+
+        if head.len() > MAX_REQUEST_HEAD_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Request headers are too large.",
+            ));
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&head).to_string())
+}
+
+async fn handle_connection(
+    workspace: &Workspace,
+    mut stream: TcpStream,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let request = read_request_head(&mut stream).await?;
+
+    // We only need the request line: METHOD URL VERSION
+    let mut parts = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let url = parts.next().unwrap_or("/");
+
+    let (status, content_type, body) = handle_request(workspace, method, url);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+
+    Ok(())
+}
+
+/// Start a local HTTP server that serves an interactive graph explorer,
+/// and a JSON API for projects and their dependency graphs.
+pub async fn serve(
+    workspace: Workspace,
+    host: &str,
+    port: u16,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind((host, port)).await?;
+
+    println!(
+        "Graph explorer running at {}",
+        color::url(&format!("http://{}:{}", host, port))
+    );
+    println!("Press Ctrl+C to stop the server");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+
+        // Requests are cheap, so handle them one at a time against the same workspace
+        if let Err(error) = handle_connection(&workspace, stream).await {
+            eprintln!("{}", color::failure(&error.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_components() {
+        assert_eq!(decode_component("app%3Abuild"), "app:build");
+        assert_eq!(decode_component("%23frontend%3Alint"), "#frontend:lint");
+        assert_eq!(decode_component("a+b"), "a+b");
+        assert_eq!(decode_component("100%"), "100%");
+    }
+
+    #[test]
+    fn decodes_plus_as_space_in_query_only() {
+        let (path, query) = parse_url("/api/a+b?label=c+d&plus=e%2Bf");
+
+        assert_eq!(path, "/api/a+b");
+        assert_eq!(query.get("label").unwrap(), "c d");
+        assert_eq!(query.get("plus").unwrap(), "e+f");
+    }
+
+    #[test]
+    fn parses_path_and_query() {
+        let (path, query) = parse_url("/api/path?from=app&to=web%2Dapp");
+
+        assert_eq!(path, "/api/path");
+        assert_eq!(query.get("from").unwrap(), "app");
+        assert_eq!(query.get("to").unwrap(), "web-app");
+    }
+
+    mod route_request {
+        use super::*;
+
+        #[test]
+        fn routes_page() {
+            assert_eq!(route_request("GET", "/"), Ok(Route::Page));
+            assert_eq!(route_request("GET", "/index.html"), Ok(Route::Page));
+        }
+
+        #[test]
+        fn routes_projects() {
+            assert_eq!(route_request("GET", "/api/projects"), Ok(Route::Projects));
+            assert_eq!(
+                route_request("GET", "/api/projects/web-app"),
+                Ok(Route::Project("web-app".to_owned()))
+            );
+        }
+
+        #[test]
+        fn routes_path() {
+            assert_eq!(
+                route_request("GET", "/api/path?from=app&to=web"),
+                Ok(Route::Path("app".to_owned(), "web".to_owned()))
+            );
+        }
+
+        #[test]
+        fn routes_dep_graph() {
+            assert_eq!(
+                route_request("GET", "/api/dep-graph?target=app%3Abuild"),
+                Ok(Route::DepGraph("app:build".to_owned()))
+            );
+        }
+
+        #[test]
+        fn errors_for_non_get_methods() {
+            let (status, _, _) = route_request("POST", "/api/projects").unwrap_err();
+
+            assert_eq!(status, "405 Method Not Allowed");
+        }
+
+        #[test]
+        fn errors_for_missing_params() {
+            let (status, _, _) = route_request("GET", "/api/path?from=app").unwrap_err();
+
+            assert_eq!(status, "400 Bad Request");
+
+            let (status, _, _) = route_request("GET", "/api/dep-graph").unwrap_err();
+
+            assert_eq!(status, "400 Bad Request");
+        }
+
+        #[test]
+        fn errors_for_unknown_paths() {
+            let (status, _, _) = route_request("GET", "/api/unknown").unwrap_err();
+
+            assert_eq!(status, "404 Not Found");
+        }
+    }
+
+    mod read_request_head {
+        use super::*;
+
+        #[tokio::test]
+        async fn reads_across_multiple_reads() {
+            let mut reader = (&b"GET /api/dep-graph?target=app%3Abuild HTTP/1.1\r\n"[..])
+                .chain(&b"Host: localhost\r\n"[..])
+                .chain(&b"\r\n"[..]);
+
+            let head = read_request_head(&mut reader).await.unwrap();
+
+            assert!(head.starts_with("GET /api/dep-graph?target=app%3Abuild HTTP/1.1\r\n"));
+            assert!(head.ends_with("Host: localhost\r\n\r\n"));
+        }
+
+        #[tokio::test]
+        async fn errors_for_large_headers() {
+            let header = format!("X-Large: {}\r\n", "a".repeat(MAX_REQUEST_HEAD_SIZE));
+            let mut reader = (&b"GET / HTTP/1.1\r\n"[..]).chain(header.as_bytes());
+
+            assert!(read_request_head(&mut reader).await.is_err());
+        }
+    }
+}
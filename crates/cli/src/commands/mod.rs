@@ -1,8 +1,12 @@
 pub mod bin;
+pub mod check;
 pub mod ci;
+pub mod dep_graph;
+pub mod graph_server;
 pub mod init;
 pub mod project;
 pub mod project_graph;
+pub mod query;
 pub mod run;
 pub mod setup;
 pub mod teardown;
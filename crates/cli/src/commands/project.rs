@@ -1,6 +1,7 @@
 use console::Term;
 use itertools::Itertools;
 use moon_logger::color;
+use moon_project::DependencySource;
 use moon_terminal::{ExtendedTerm, Label};
 use moon_utils::is_test_env;
 use moon_workspace::Workspace;
@@ -27,42 +28,45 @@ pub async fn project(id: &str, json: bool) -> Result<(), Box<dyn std::error::Err
         term.render_entry("Root", &color::path(&project.root))?;
     }
 
-    if let Some(config) = project.config {
+    if let Some(config) = &project.config {
         term.render_entry("Language", &term.format(&config.language))?;
         term.render_entry("Type", &term.format(&config.type_of))?;
 
-        if let Some(meta) = config.project {
+        if !config.tags.is_empty() {
+            term.render_entry_list("Tags", &config.tags)?;
+        }
+
+        if let Some(meta) = &config.project {
             term.render_entry("Name", &meta.name)?;
             term.render_entry("Description", &meta.description)?;
             term.render_entry("Owner", &meta.owner)?;
             term.render_entry_list("Maintainers", &meta.maintainers)?;
             term.render_entry("Channel", &meta.channel)?;
         }
+    }
 
-        let mut deps = vec![];
-
-        for dep_id in config.depends_on {
-            match workspace.projects.load(&dep_id) {
-                Ok(dep) => {
-                    deps.push(format!(
-                        "{} {}{}{}",
-                        color::id(&dep_id),
-                        color::muted_light("("),
-                        color::file(&dep.source),
-                        color::muted_light(")"),
-                    ));
-                }
-                Err(_) => {
-                    deps.push(color::id(&dep_id));
-                }
-            };
-        }
+    let mut deps = vec![];
+
+    for (dep_id, dep_source) in workspace.projects.get_dependency_sources_of(&project)? {
+        let dep = workspace.projects.load(&dep_id)?;
+
+        deps.push(format!(
+            "{} {}{}{} {}",
+            color::id(&dep_id),
+            color::muted_light("("),
+            color::file(&dep.source),
+            color::muted_light(")"),
+            color::muted_light(match dep_source {
+                DependencySource::Explicit(_) => "[explicit]",
+                DependencySource::Inferred(_) => "[inferred]",
+            }),
+        ));
+    }
 
-        if !deps.is_empty() {
-            term.write_line("")?;
-            term.render_label(Label::Default, "Depends on")?;
-            term.render_list(&deps)?;
-        }
+    if !deps.is_empty() {
+        term.write_line("")?;
+        term.render_label(Label::Default, "Depends on")?;
+        term.render_list(&deps)?;
     }
 
     if !project.tasks.is_empty() {
@@ -72,10 +76,18 @@ pub async fn project(id: &str, json: bool) -> Result<(), Box<dyn std::error::Err
         for name in project.tasks.keys().sorted() {
             let task = project.tasks.get(name).unwrap();
 
-            term.render_entry(
-                name,
-                &color::shell(&format!("{} {}", task.command, task.args.join(" "))),
-            )?;
+            // Variants are listed through their matrix task
+            if task.is_variant() {
+                continue;
+            }
+
+            let mut command = color::shell(&format!("{} {}", task.command, task.args.join(" ")));
+
+            if !task.variants.is_empty() {
+                command = format!("{} ({} variants)", command, task.variants.len());
+            }
+
+            term.render_entry(name, &command)?;
         }
     }
 
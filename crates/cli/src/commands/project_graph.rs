@@ -1,6 +1,16 @@
+use crate::commands::graph_server::serve;
 use moon_workspace::Workspace;
 
-pub async fn project_graph(id: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+pub struct ProjectGraphOptions {
+    pub host: String,
+    pub port: u16,
+    pub serve: bool,
+}
+
+pub async fn project_graph(
+    id: &Option<String>,
+    options: ProjectGraphOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
 
     // Force load projects into the graph
@@ -12,6 +22,10 @@ pub async fn project_graph(id: &Option<String>) -> Result<(), Box<dyn std::error
         }
     }
 
+    if options.serve {
+        return serve(workspace, &options.host, options.port).await;
+    }
+
     println!("{}", workspace.projects.to_dot());
 
     Ok(())
@@ -0,0 +1,232 @@
+use crate::commands::run::{get_touched_files, RunStatus};
+use moon_config::{ProjectLanguage, ProjectType};
+use moon_project::{Project, TouchedFilePaths};
+use moon_utils::glob;
+use moon_workspace::Workspace;
+use serde_json::json;
+
+pub struct QueryOptions {
+    pub affected: bool,
+    pub id: Option<String>,
+    pub json: bool,
+    pub language: Option<ProjectLanguage>,
+    pub source: Option<String>,
+    pub status: RunStatus,
+    pub tags: Vec<String>,
+    pub type_of: Option<ProjectType>,
+    pub upstream: bool,
+}
+
+pub struct QueryTouchedFilesOptions {
+    pub json: bool,
+    pub status: RunStatus,
+    pub upstream: bool,
+}
+
+fn matches_enum<T: PartialEq>(value: &T, filter: &Option<T>) -> bool {
+    match filter {
+        Some(filter) => value == filter,
+        None => true,
+    }
+}
+
+fn matches_glob(pattern: &Option<String>, value: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    match pattern {
+        Some(pattern) => Ok(glob::matches_id(pattern, value)?),
+        None => Ok(true),
+    }
+}
+
+fn get_language(project: &Project) -> ProjectLanguage {
+    project
+        .config
+        .as_ref()
+        .map(|config| config.language.clone())
+        .unwrap_or_default()
+}
+
+/// Load all projects into the graph, and return those that match the filters,
+/// excluding the affected filter, which differs between projects and tasks.
+async fn find_projects(
+    workspace: &Workspace,
+    options: &QueryOptions,
+) -> Result<(Vec<Project>, Option<TouchedFilePaths>), Box<dyn std::error::Error>> {
+    let touched_files = if options.affected {
+        Some(get_touched_files(workspace, &options.status, options.upstream).await?)
+    } else {
+        None
+    };
+
+    let mut projects = vec![];
+
+    for project_id in workspace.projects.ids() {
+        let project = workspace.projects.load(&project_id)?;
+
+        if !matches_glob(&options.id, &project.id)?
+            || !matches_glob(&options.source, &project.source)?
+        {
+            continue;
+        }
+
+        if !matches_enum(&get_language(&project), &options.language)
+            || !matches_enum(&project.get_type(), &options.type_of)
+        {
+            continue;
+        }
+
+        // Only include projects that match at least 1 tag, when filtering
+        if !options.tags.is_empty() && !options.tags.iter().any(|tag| project.has_tag(tag)) {
+            continue;
+        }
+
+        projects.push(project);
+    }
+
+    Ok((projects, touched_files))
+}
+
+fn get_sorted_targets(project: &Project) -> Vec<String> {
+    let mut targets = project
+        .tasks
+        .values()
+        .filter(|task| !task.is_variant())
+        .map(|task| task.target.clone())
+        .collect::<Vec<String>>();
+
+    targets.sort();
+    targets
+}
+
+pub async fn query_projects(options: QueryOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let (mut projects, touched_files) = find_projects(&workspace, &options).await?;
+
+    if let Some(touched) = &touched_files {
+        projects.retain(|project| project.get_touched_file(touched).is_some());
+    }
+
+    if options.json {
+        let projects = projects
+            .iter()
+            .map(|project| {
+                json!({
+                    "id": project.id,
+                    "source": project.source,
+                    "root": project.root,
+                    "language": get_language(project),
+                    "type": project.get_type(),
+                    "tags": project
+                        .config
+                        .as_ref()
+                        .map(|config| config.tags.clone())
+                        .unwrap_or_default(),
+                    "tasks": get_sorted_targets(project),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "projects": projects }))?
+        );
+
+        return Ok(());
+    }
+
+    for project in projects {
+        println!("{} | {}", project.id, project.source);
+    }
+
+    Ok(())
+}
+
+pub async fn query_tasks(options: QueryOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let (projects, touched_files) = find_projects(&workspace, &options).await?;
+    let mut tasks = vec![];
+
+    for project in &projects {
+        let mut project_tasks = project.tasks.values().collect::<Vec<_>>();
+        project_tasks.sort_by(|a, b| a.target.cmp(&b.target));
+
+        for task in project_tasks {
+            // Variants are listed through their matrix task
+            if task.is_variant() {
+                continue;
+            }
+
+            // Tasks may be affected by inputs outside of their project
+            if let Some(touched) = &touched_files {
+                if !task.is_affected(touched)? {
+                    continue;
+                }
+            }
+
+            tasks.push((project, task));
+        }
+    }
+
+    if options.json {
+        let tasks = tasks
+            .iter()
+            .map(|(project, task)| {
+                json!({
+                    "target": task.target,
+                    "project": project.id,
+                    "projectRoot": project.root,
+                    "command": task.command,
+                    "args": task.args,
+                    "type": task.type_of,
+                    "variants": task.variants,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "tasks": tasks }))?
+        );
+
+        return Ok(());
+    }
+
+    for (_, task) in tasks {
+        println!("{}", task.target);
+    }
+
+    Ok(())
+}
+
+pub async fn query_touched_files(
+    options: QueryTouchedFilesOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let touched_files = get_touched_files(&workspace, &options.status, options.upstream).await?;
+
+    let mut files = touched_files
+        .iter()
+        .map(|file| {
+            file.strip_prefix(&workspace.root)
+                .unwrap_or(file)
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+
+    files.sort();
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "files": files }))?
+        );
+
+        return Ok(());
+    }
+
+    for file in files {
+        println!("{}", file);
+    }
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
 use clap::ArgEnum;
 use console::Term;
 use moon_logger::color;
-use moon_project::{Target, TouchedFilePaths};
+use moon_project::{Target, TargetID, TouchedFilePaths};
 use moon_terminal::ExtendedTerm;
 use moon_utils::time;
-use moon_workspace::{Action, ActionRunner, ActionStatus, DepGraph, Workspace, WorkspaceError};
+use moon_workspace::{
+    Action, ActionRunner, ActionStatus, AffectedBy, DepGraph, Workspace, WorkspaceError,
+};
 use std::collections::HashSet;
+use std::path::Path;
 use std::string::ToString;
 use std::time::Duration;
 use strum_macros::Display;
@@ -35,7 +38,7 @@ pub struct RunOptions {
     pub upstream: bool,
 }
 
-async fn get_touched_files(
+pub async fn get_touched_files(
     workspace: &Workspace,
     status: &RunStatus,
     upstream: bool,
@@ -68,6 +71,50 @@ async fn get_touched_files(
     Ok(touched)
 }
 
+fn format_affected_source(workspace: &Workspace, source: &str) -> String {
+    if source.starts_with('$') {
+        return format!("changed env var {}", color::symbol(source));
+    }
+
+    let path = Path::new(source);
+
+    format!(
+        "touched file {}",
+        color::file(
+            &path
+                .strip_prefix(&workspace.root)
+                .unwrap_or(path)
+                .to_string_lossy()
+        )
+    )
+}
+
+/// Explain why each explicitly requested target is affected by touched files.
+fn render_affected_reasons(workspace: &Workspace, dep_graph: &DepGraph) {
+    let mut primary_targets = dep_graph.primary_targets.iter().collect::<Vec<_>>();
+    primary_targets.sort();
+
+    for target_id in primary_targets {
+        let reason = match dep_graph.affected_by.get(target_id) {
+            Some(AffectedBy::Global) => String::from("touched moon configuration files"),
+            Some(AffectedBy::Input(source)) => format_affected_source(workspace, source),
+            Some(AffectedBy::Project(project_id, source)) => format!(
+                "upstream project {} ({})",
+                color::id(project_id),
+                format_affected_source(workspace, source)
+            ),
+            Some(AffectedBy::Target(dep_target_id, source)) => format!(
+                "upstream target {} ({})",
+                color::target(dep_target_id),
+                format_affected_source(workspace, source)
+            ),
+            None => continue,
+        };
+
+        println!("Target {} affected by {}", color::target(target_id), reason);
+    }
+}
+
 pub fn render_result_stats(
     results: Vec<Action>,
     duration: Duration,
@@ -150,56 +197,87 @@ pub fn render_result_stats(
     Ok(())
 }
 
-pub async fn run(target_id: &str, options: RunOptions) -> Result<(), Box<dyn std::error::Error>> {
-    let target = Target::parse(target_id)?;
+pub async fn run(
+    target_ids: &[TargetID],
+    options: RunOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let targets = target_ids
+        .iter()
+        .map(|id| Target::parse(id))
+        .collect::<Result<Vec<Target>, _>>()?;
     let workspace = Workspace::load().await?;
+    let targets_list = target_ids
+        .iter()
+        .map(|id| color::target(id))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let (targets_label, targets_title) = if target_ids.len() == 1 {
+        ("target", "Target")
+    } else {
+        ("targets", "Targets")
+    };
 
-    // Generate a dependency graph for all the targets that need to be ran
+    // Generate a dependency graph for all the targets that need to be ran.
+    // All targets are inserted into the same graph, so that shared deps are only ran once.
     let mut dep_graph = DepGraph::default();
+    let mut inserted_count = 0;
 
     if options.affected {
         let touched_files =
             get_touched_files(&workspace, &options.status, options.upstream).await?;
-        let inserted_count =
-            dep_graph.run_target(&target, &workspace.projects, Some(&touched_files))?;
+
+        dep_graph.use_cache(&workspace.cache);
+
+        for target in &targets {
+            inserted_count +=
+                dep_graph.run_target(target, &workspace.projects, Some(&touched_files))?;
+        }
 
         if inserted_count == 0 {
             if matches!(options.status, RunStatus::All) {
                 println!(
-                    "Target {} not affected by touched files",
-                    color::target(target_id)
+                    "{} {} not affected by touched files",
+                    targets_title, targets_list
                 );
             } else {
                 println!(
-                    "Target {} not affected by touched files (using status {})",
-                    color::target(target_id),
+                    "{} {} not affected by touched files (using status {})",
+                    targets_title,
+                    targets_list,
                     color::symbol(&options.status.to_string().to_lowercase())
                 );
             }
 
             return Ok(());
         }
+
+        render_affected_reasons(&workspace, &dep_graph);
     } else {
-        let inserted_count = dep_graph.run_target(&target, &workspace.projects, None)?;
+        for target in &targets {
+            inserted_count += dep_graph.run_target(target, &workspace.projects, None)?;
+        }
 
         if inserted_count == 0 {
-            println!("No tasks found for target {}", color::target(target_id));
+            println!("No tasks found for {} {}", targets_label, targets_list);
 
             return Ok(());
         }
     }
 
     if options.dependents {
-        dep_graph.run_target_dependents(&target, &workspace.projects)?;
+        for target in &targets {
+            dep_graph.run_target_dependents(target, &workspace.projects)?;
+        }
     }
 
     // Process all tasks in the graph
+    let primary_targets = dep_graph.primary_targets.clone();
     let mut runner = ActionRunner::new(workspace);
 
     let results = runner
         .bail_on_error()
         .set_passthrough_args(options.passthrough)
-        .set_primary_target(target_id)
+        .set_primary_targets(primary_targets)
         .run(dep_graph)
         .await?;
 
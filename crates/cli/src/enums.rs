@@ -29,3 +29,20 @@ impl Default for LogLevel {
         LogLevel::Info
     }
 }
+
+#[derive(ArgEnum, Clone, Debug)]
+#[clap(rename_all = "lower")]
+pub enum ProjectLanguage {
+    JavaScript,
+    TypeScript,
+    Unknown,
+}
+
+#[derive(ArgEnum, Clone, Debug)]
+#[clap(rename_all = "lower")]
+pub enum ProjectType {
+    Application,
+    Library,
+    Tool,
+    Unknown,
+}
@@ -4,18 +4,23 @@ mod enums;
 mod helpers;
 
 use crate::commands::bin::bin;
+use crate::commands::check::check;
 use crate::commands::ci::{ci, CiOptions};
+use crate::commands::dep_graph::{dep_graph, DepGraphOptions};
 use crate::commands::init::{init, InitOptions};
 use crate::commands::project::project;
-use crate::commands::project_graph::project_graph;
+use crate::commands::project_graph::{project_graph, ProjectGraphOptions};
+use crate::commands::query::{
+    query_projects, query_tasks, query_touched_files, QueryOptions, QueryTouchedFilesOptions,
+};
 use crate::commands::run::{run, RunOptions};
 use crate::commands::setup::setup;
 use crate::commands::teardown::teardown;
 use crate::helpers::setup_colors;
-use app::{App, Commands};
+use app::{App, Commands, QueryCommands, QueryFilterArgs};
 use clap::Parser;
 use console::Term;
-use enums::LogLevel;
+use enums::{LogLevel, ProjectLanguage, ProjectType};
 use moon_logger::{LevelFilter, Logger};
 use moon_terminal::ExtendedTerm;
 use std::env;
@@ -35,6 +40,37 @@ fn map_log_level(level: LogLevel) -> LevelFilter {
     }
 }
 
+fn map_project_language(language: &ProjectLanguage) -> moon_config::ProjectLanguage {
+    match language {
+        ProjectLanguage::JavaScript => moon_config::ProjectLanguage::JavaScript,
+        ProjectLanguage::TypeScript => moon_config::ProjectLanguage::TypeScript,
+        ProjectLanguage::Unknown => moon_config::ProjectLanguage::Unknown,
+    }
+}
+
+fn map_project_type(type_of: &ProjectType) -> moon_config::ProjectType {
+    match type_of {
+        ProjectType::Application => moon_config::ProjectType::Application,
+        ProjectType::Library => moon_config::ProjectType::Library,
+        ProjectType::Tool => moon_config::ProjectType::Tool,
+        ProjectType::Unknown => moon_config::ProjectType::Unknown,
+    }
+}
+
+fn map_query_options(filters: &QueryFilterArgs, json: bool) -> QueryOptions {
+    QueryOptions {
+        affected: filters.affected,
+        id: filters.id.clone(),
+        json,
+        language: filters.language.as_ref().map(map_project_language),
+        source: filters.source.clone(),
+        status: filters.status.clone(),
+        tags: filters.tags.clone(),
+        type_of: filters.type_of.as_ref().map(map_project_type),
+        upstream: filters.upstream,
+    }
+}
+
 pub async fn run_cli() {
     // Create app and parse arguments
     let args = App::parse();
@@ -56,20 +92,43 @@ pub async fn run_cli() {
     // Match and run subcommand
     let result = match &args.command {
         Commands::Bin { tool } => bin(tool).await,
+        Commands::Check => check().await,
         Commands::Ci {
             base,
             head,
             job,
             job_total,
+            tags,
         } => {
             ci(CiOptions {
                 base: base.clone(),
                 head: head.clone(),
                 job: *job,
                 job_total: *job_total,
+                tags: tags.clone(),
             })
             .await
         }
+        Commands::DepGraph {
+            targets,
+            affected,
+            dependents,
+            json,
+            status,
+            upstream,
+        } => {
+            dep_graph(
+                targets,
+                DepGraphOptions {
+                    affected: *affected,
+                    dependents: *dependents,
+                    json: *json,
+                    status: status.clone(),
+                    upstream: *upstream,
+                },
+            )
+            .await
+        }
         Commands::Init {
             dest,
             force,
@@ -89,9 +148,44 @@ pub async fn run_cli() {
             .await
         }
         Commands::Project { id, json } => project(id, *json).await,
-        Commands::ProjectGraph { id } => project_graph(id).await,
+        Commands::ProjectGraph {
+            id,
+            serve,
+            host,
+            port,
+        } => {
+            project_graph(
+                id,
+                ProjectGraphOptions {
+                    serve: *serve,
+                    host: host.clone(),
+                    port: *port,
+                },
+            )
+            .await
+        }
+        Commands::Query { command } => match command {
+            QueryCommands::Projects { filters, json } => {
+                query_projects(map_query_options(filters, *json)).await
+            }
+            QueryCommands::Tasks { filters, json } => {
+                query_tasks(map_query_options(filters, *json)).await
+            }
+            QueryCommands::TouchedFiles {
+                status,
+                upstream,
+                json,
+            } => {
+                query_touched_files(QueryTouchedFilesOptions {
+                    json: *json,
+                    status: status.clone(),
+                    upstream: *upstream,
+                })
+                .await
+            }
+        },
         Commands::Run {
-            target,
+            targets,
             affected,
             dependents,
             status,
@@ -99,7 +193,7 @@ pub async fn run_cli() {
             upstream,
         } => {
             run(
-                target,
+                targets,
                 RunOptions {
                     affected: *affected,
                     dependents: *dependents,
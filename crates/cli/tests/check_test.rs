@@ -0,0 +1,40 @@
+use moon_utils::test::{create_moon_command, get_assert_output};
+use predicates::prelude::*;
+
+#[test]
+fn passes_without_constraints() {
+    let assert = create_moon_command("projects").arg("check").assert();
+
+    let output = get_assert_output(&assert);
+
+    assert.success();
+    assert!(
+        predicate::str::contains("All projects satisfy the workspace constraints").eval(&output)
+    );
+}
+
+#[test]
+fn reports_all_violations() {
+    let assert = create_moon_command("project-graph/constraints")
+        .arg("check")
+        .assert();
+
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+    assert!(predicate::str::contains("Found 2 project constraint violation(s).").eval(&output));
+    assert!(predicate::str::contains("projects may not be dependencies").eval(&output));
+    assert!(predicate::str::contains("may not depend on projects tagged").eval(&output));
+}
+
+#[test]
+fn reports_all_alias_collisions() {
+    let assert = create_moon_command("project-graph/aliases-collision")
+        .arg("check")
+        .assert();
+
+    let output = get_assert_output(&assert);
+
+    assert.failure();
+    assert!(predicate::str::contains("Found 2 project alias collision(s).").eval(&output));
+}
@@ -0,0 +1,42 @@
+use moon_utils::test::{create_moon_command, get_assert_output};
+use predicates::prelude::*;
+
+#[test]
+fn outputs_dot_format() {
+    let assert = create_moon_command("cases")
+        .arg("dep-graph")
+        .arg("base:runFromProject")
+        .assert();
+
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("digraph {").eval(&output));
+    assert!(predicate::str::contains("RunTarget(base:runFromProject)").eval(&output));
+}
+
+#[test]
+fn outputs_json_format() {
+    let assert = create_moon_command("cases")
+        .arg("dep-graph")
+        .arg("base:runFromProject")
+        .arg("--json")
+        .assert();
+
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("\"nodes\"").eval(&output));
+    assert!(predicate::str::contains("\"edges\"").eval(&output));
+    assert!(
+        predicate::str::contains("\"label\": \"RunTarget(base:runFromProject)\"").eval(&output)
+    );
+}
+
+#[test]
+fn includes_all_tasks_when_no_targets() {
+    let assert = create_moon_command("projects").arg("dep-graph").assert();
+
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("RunTarget(tasks:test)").eval(&output));
+    assert!(predicate::str::contains("RunTarget(tasks:lint)").eval(&output));
+}
@@ -0,0 +1,231 @@
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command, create_moon_command_in, get_assert_output,
+};
+use predicates::prelude::*;
+use std::fs;
+
+mod projects {
+    use super::*;
+
+    #[test]
+    fn lists_all_projects() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("advanced | advanced").eval(&output));
+        assert!(predicate::str::contains("foo | deps/foo").eval(&output));
+        assert!(predicate::str::contains("noConfig | no-config").eval(&output));
+    }
+
+    #[test]
+    fn filters_by_id_pattern() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--id")
+            .arg("ba*")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("bar | deps/bar").eval(&output));
+        assert!(predicate::str::contains("basic | basic").eval(&output));
+        assert!(predicate::str::contains("baz | deps/baz").eval(&output));
+        assert!(!predicate::str::contains("advanced").eval(&output));
+    }
+
+    #[test]
+    fn filters_by_source_pattern() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--source")
+            .arg("deps/*")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("foo | deps/foo").eval(&output));
+        assert!(!predicate::str::contains("basic").eval(&output));
+    }
+
+    #[test]
+    fn filters_by_type() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--type")
+            .arg("application")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("advanced | advanced").eval(&output));
+        assert!(!predicate::str::contains("tasks | tasks").eval(&output));
+    }
+
+    #[test]
+    fn filters_by_language() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--language")
+            .arg("javascript")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("basic | basic").eval(&output));
+        assert!(!predicate::str::contains("advanced").eval(&output));
+    }
+
+    #[test]
+    fn errors_for_unknown_language() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--language")
+            .arg("rust")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert.failure();
+        assert!(
+            predicate::str::contains("possible values: javascript, typescript, unknown")
+                .eval(&output)
+        );
+    }
+
+    #[test]
+    fn filters_by_affected() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        fs::write(fixture.path().join("basic/file.ts"), "export {};").unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("projects")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("basic | basic").eval(&output));
+        assert!(!predicate::str::contains("advanced").eval(&output));
+        assert!(!predicate::str::contains("tasks | tasks").eval(&output));
+    }
+
+    #[test]
+    fn outputs_json_with_roots_and_targets() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("projects")
+            .arg("--id")
+            .arg("tasks")
+            .arg("--json")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("\"id\": \"tasks\"").eval(&output));
+        assert!(predicate::str::contains("\"root\"").eval(&output));
+        assert!(predicate::str::contains("\"tasks:lint\"").eval(&output));
+        assert!(predicate::str::contains("\"tasks:test\"").eval(&output));
+        assert!(!predicate::str::contains("\"tasks:e2e[").eval(&output));
+    }
+}
+
+mod tasks {
+    use super::*;
+
+    #[test]
+    fn lists_tasks_of_matching_projects() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("tasks")
+            .arg("--id")
+            .arg("tasks")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("tasks:lint\n").eval(&output));
+        assert!(predicate::str::contains("tasks:test\n").eval(&output));
+    }
+
+    #[test]
+    fn lists_matrix_tasks_without_variants() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("tasks")
+            .arg("--id")
+            .arg("tasks")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("tasks:e2e\n").eval(&output));
+        assert!(!predicate::str::contains("tasks:e2e[").eval(&output));
+    }
+
+    #[test]
+    fn filters_by_affected() {
+        let fixture = create_fixtures_sandbox("projects");
+        let config_path = fixture.path().join("tasks/project.yml");
+        let config = fs::read_to_string(&config_path).unwrap();
+
+        fs::write(&config_path, format!("{}\n# Touched", config)).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("tasks")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("tasks:lint\n").eval(&output));
+        assert!(predicate::str::contains("tasks:test\n").eval(&output));
+    }
+
+    #[test]
+    fn skips_unaffected_tasks() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        fs::write(fixture.path().join("basic/file.ts"), "export {};").unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("tasks")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(!predicate::str::contains("tasks:lint").eval(&output));
+        assert!(!predicate::str::contains("tasks:test").eval(&output));
+    }
+
+    #[test]
+    fn outputs_json() {
+        let assert = create_moon_command("projects")
+            .arg("query")
+            .arg("tasks")
+            .arg("--id")
+            .arg("tasks")
+            .arg("--json")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("\"target\": \"tasks:test\"").eval(&output));
+        assert!(predicate::str::contains("\"project\": \"tasks\"").eval(&output));
+        assert!(predicate::str::contains("\"command\": \"jest\"").eval(&output));
+        assert!(predicate::str::contains("\"e2e[browser=chromium]\"").eval(&output));
+    }
+}
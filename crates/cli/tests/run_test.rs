@@ -280,6 +280,125 @@ mod dependencies {
     }
 }
 
+#[cfg(not(windows))]
+mod affected {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn doesnt_run_if_not_affected() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("aliasTarget:build")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(
+            predicate::str::contains("Target aliasTarget:build not affected by touched files")
+                .eval(&output)
+        );
+    }
+
+    #[test]
+    fn explains_when_affected_by_input() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        fs::write(fixture.path().join("alias-target/file.txt"), "changed").unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("aliasTarget:build")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains(
+            "Target aliasTarget:build affected by touched file alias-target/file.txt"
+        )
+        .eval(&output));
+        assert!(predicate::str::contains("Tasks: 1 completed").eval(&output));
+    }
+
+    #[test]
+    fn explains_when_affected_by_upstream_target() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        fs::write(fixture.path().join("alias-target/file.txt"), "changed").unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("aliasDeps:build")
+            .arg("--affected")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains(
+            "Target aliasDeps:build affected by upstream target aliasTarget:build (touched file alias-target/file.txt)"
+        )
+        .eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+}
+
+#[cfg(not(windows))]
+mod aliases {
+    use super::*;
+
+    #[test]
+    fn runs_target_by_project_alias() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("@cases/alias-target:build")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("aliasTarget:build").eval(&output));
+        assert!(predicate::str::contains("alias-target").eval(&output));
+        assert!(predicate::str::contains("Tasks: 1 completed").eval(&output));
+    }
+
+    #[test]
+    fn runs_aliased_dep_once() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("aliasDeps:build")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("aliasTarget:build").eval(&output));
+        assert!(predicate::str::contains("aliasDeps:build").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+
+    #[test]
+    fn runs_optional_aliased_dep() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("aliasDeps:optional")
+            .assert();
+
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("aliasTarget:build").eval(&output));
+        assert!(predicate::str::contains("alias-optional").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+}
+
 mod target_scopes {
     use super::*;
 
@@ -356,6 +475,98 @@ mod target_scopes {
         assert!(predicate::str::contains("selfOther").eval(&output));
         assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
     }
+
+    #[test]
+    fn supports_multiple_targets_with_mixed_scopes() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg(":all")
+            .arg("targetScopeA:deps")
+            .arg("targetScopeB:self")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("scope=all").eval(&output));
+        assert!(predicate::str::contains("scope=deps").eval(&output));
+        assert!(predicate::str::contains("scope=self").eval(&output));
+        assert!(predicate::str::contains("Tasks: 9 completed").eval(&output));
+    }
+
+    #[test]
+    fn supports_project_patterns() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScope*:all")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeA:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeB:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeC:all").eval(&output));
+        assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+    }
+
+    #[test]
+    fn supports_task_patterns() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScopeB:self*")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeB:self").eval(&output));
+        assert!(predicate::str::contains("targetScopeB:selfOther").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+
+    #[test]
+    fn supports_tag_scope() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("#frontend:all")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("targetScopeA:all").eval(&output));
+        assert!(!predicate::str::contains("targetScopeB:all").eval(&output));
+        assert!(predicate::str::contains("targetScopeC:all").eval(&output));
+        assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+    }
+
+    #[test]
+    fn errors_for_patterns_without_matches() {
+        let assert = create_moon_command("cases")
+            .arg("run")
+            .arg("targetScope*:unknown-*")
+            .assert();
+
+        assert
+            .failure()
+            .stderr(predicate::str::contains("did not match any project tasks"));
+    }
+
+    #[test]
+    fn dedupes_shared_deps_across_multiple_targets() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("targetScopeA:deps")
+            .arg("depsA:standard")
+            .arg("depsB:standard")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("Tasks: 4 completed").eval(&output));
+    }
 }
 
 mod node {
@@ -1048,6 +1259,99 @@ mod system {
 
         assert_snapshot!(get_assert_output(&assert));
     }
+
+    #[test]
+    fn doesnt_run_mutex_tasks_concurrently() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:mutex")
+            .assert();
+        let output = get_assert_output(&assert);
+
+        assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+    }
+
+    mod outputs {
+        use super::*;
+
+        #[test]
+        fn passes_when_outputs_exist() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:outputs")
+                .assert();
+
+            assert.success();
+            assert!(fixture.path().join("system/build/file.txt").exists());
+        }
+
+        #[test]
+        fn errors_when_outputs_missing() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:outputsMissing")
+                .assert();
+            let output = get_assert_output(&assert);
+
+            assert!(predicate::str::contains(
+                "ran successfully, but its declared output system/build-missing does not exist"
+            )
+            .eval(&output));
+        }
+
+        #[test]
+        fn passes_when_output_globs_match() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:outputsGlob")
+                .assert();
+
+            assert.success();
+        }
+
+        #[test]
+        fn errors_when_output_globs_dont_match() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:outputsGlobMissing")
+                .assert();
+            let output = get_assert_output(&assert);
+
+            assert!(predicate::str::contains(
+                "ran successfully, but its declared output system/build/*.js does not exist"
+            )
+            .eval(&output));
+        }
+
+        #[test]
+        fn warns_for_undeclared_outputs() {
+            let fixture = create_fixtures_sandbox("cases");
+
+            let assert = create_moon_command_in(fixture.path())
+                .arg("run")
+                .arg("system:outputsUndeclared")
+                .assert();
+            // Logs are filtered from the assert output, so read stderr directly
+            let output = String::from_utf8(assert.get_output().stderr.to_owned()).unwrap();
+
+            assert!(
+                predicate::str::contains("created files that are not declared as outputs")
+                    .eval(&output)
+            );
+            assert!(predicate::str::contains("extra.txt").eval(&output));
+            assert!(predicate::str::contains("extra/nested.txt").eval(&output));
+        }
+    }
 }
 
 #[cfg(windows)]
@@ -10,7 +10,9 @@ mod workspace;
 
 pub use errors::format_errors;
 pub use project::global::GlobalProjectConfig;
-pub use project::task::{TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType};
+pub use project::task::{
+    TaskConfig, TaskDependencyConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
+};
 pub use project::{ProjectConfig, ProjectLanguage, ProjectMetadataConfig, ProjectType};
 pub use types::{FileGlob, FilePath, FilePathOrGlob, ProjectID, TargetID, TaskID};
 pub use validator::ValidationErrors;
@@ -18,8 +20,8 @@ pub use workspace::node::{
     default_node_version, default_npm_version, default_pnpm_version, default_yarn_version,
 };
 pub use workspace::{
-    NodeConfig, NpmConfig, PackageManager, PnpmConfig, TypeScriptConfig, VcsConfig, VcsManager,
-    WorkspaceConfig, YarnConfig,
+    ConstraintsConfig, NodeConfig, NpmConfig, PackageManager, PnpmConfig, TypeScriptConfig,
+    VcsConfig, VcsManager, WorkspaceConfig, YarnConfig,
 };
 
 pub fn load_workspace_config_template() -> &'static str {
@@ -3,6 +3,7 @@
 use crate::constants;
 use crate::errors::{create_validation_error, map_figment_error_to_validation_errors};
 use crate::project::task::TaskConfig;
+use crate::project::{ProjectLanguage, ProjectType};
 use crate::providers::url::Url;
 use crate::types::FileGroups;
 use crate::validators::{validate_extends, validate_id};
@@ -29,10 +30,11 @@ fn validate_tasks(map: &HashMap<String, TaskConfig>) -> Result<(), ValidationErr
     for (name, task) in map {
         validate_id(&format!("tasks.{}", name), name)?;
 
-        // Fail for both `None` and empty strings
+        // Fail for both `None` and empty strings,
+        // unless the command is inherited from an extended task
         let command = task.command.clone().unwrap_or_default();
 
-        if command.is_empty() {
+        if command.is_empty() && task.extends.is_none() {
             return Err(create_validation_error(
                 "required_command",
                 &format!("tasks.{}.command", name),
@@ -45,7 +47,7 @@ fn validate_tasks(map: &HashMap<String, TaskConfig>) -> Result<(), ValidationErr
 }
 
 /// Docs: https://moonrepo.dev/docs/config/global-project
-#[derive(Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct GlobalProjectConfig {
     #[validate(custom = "validate_extends")]
@@ -60,6 +62,18 @@ pub struct GlobalProjectConfig {
     #[validate]
     pub tasks: HashMap<String, TaskConfig>,
 
+    /// File groups and tasks only inherited by projects of a specific language,
+    /// loaded from `.moon/project.<language>.yml`.
+    #[schemars(skip)]
+    #[serde(skip)]
+    pub languages: HashMap<ProjectLanguage, GlobalProjectConfig>,
+
+    /// File groups and tasks only inherited by projects of a specific type,
+    /// loaded from `.moon/project.<type>.yml`.
+    #[schemars(skip)]
+    #[serde(skip)]
+    pub types: HashMap<ProjectType, GlobalProjectConfig>,
+
     /// JSON schema URI.
     #[serde(skip, rename = "$schema")]
     pub schema: String,
@@ -122,6 +136,31 @@ impl GlobalProjectConfig {
         Ok(config)
     }
 
+    /// Return a config with the file groups and tasks that apply to a project of the
+    /// provided language and type. Type scoped settings take precedence over language.
+    pub fn scoped_to(
+        &self,
+        language: &ProjectLanguage,
+        type_of: &ProjectType,
+    ) -> GlobalProjectConfig {
+        let mut config = GlobalProjectConfig {
+            extends: self.extends.clone(),
+            file_groups: self.file_groups.clone(),
+            tasks: self.tasks.clone(),
+            ..GlobalProjectConfig::default()
+        };
+
+        for scoped in [self.languages.get(language), self.types.get(type_of)]
+            .into_iter()
+            .flatten()
+        {
+            config.file_groups.extend(scoped.file_groups.clone());
+            config.tasks.extend(scoped.tasks.clone());
+        }
+
+        config
+    }
+
     fn load_config(figment: Figment) -> Result<GlobalProjectConfig, ValidationErrors> {
         let config: GlobalProjectConfig = match figment.extract() {
             Ok(cfg) => cfg,
@@ -173,6 +212,8 @@ fileGroups:
                         string_vec!["src/**/*"]
                     )]),
                     tasks: HashMap::new(),
+                    languages: HashMap::new(),
+                    types: HashMap::new(),
                     schema: String::new(),
                 }
             );
@@ -304,11 +345,13 @@ fileGroups:
                     TaskConfig {
                         command: Some(String::from("e")),
                         options: TaskOptionsConfig {
+                            env_file: None,
                             merge_args: None,
                             merge_deps: None,
                             merge_env: None,
                             merge_inputs: None,
                             merge_outputs: None,
+                            mutex: None,
                             retry_count: None,
                             run_in_ci: Some(false),
                             run_from_workspace_root: None,
@@ -522,5 +565,26 @@ tasks:
                 Ok(())
             });
         }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid matrix value")]
+        fn invalid_matrix_value() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    r#"
+tasks:
+    test:
+        command: jest
+        matrix:
+            browser: [chrome/latest]
+"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
     }
 }
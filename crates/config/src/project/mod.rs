@@ -5,20 +5,33 @@ pub mod task;
 
 use crate::constants;
 use crate::errors::{create_validation_error, map_figment_error_to_validation_errors};
-use crate::types::{FileGroups, ProjectID, TaskID};
+use crate::types::{FileGroups, FilePath, ProjectID, TaskID};
 use crate::validators::validate_id;
 use figment::value::{Dict, Map};
 use figment::{
     providers::{Format, Serialized, Yaml},
     Figment, Metadata, Profile, Provider,
 };
+use moon_utils::regex::matches_alias;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use task::TaskConfig;
+use task::{deserialize_env_file, make_env_file_schema, validate_env_file, TaskConfig};
 use validator::{Validate, ValidationError, ValidationErrors};
 
+fn validate_alias(alias: &str) -> Result<(), ValidationError> {
+    if !matches_alias(alias) {
+        return Err(create_validation_error(
+            "invalid_alias",
+            "alias",
+            String::from("Must be a valid ID or a scoped package name (@scope/name)."),
+        ));
+    }
+
+    Ok(())
+}
+
 fn validate_file_groups(map: &FileGroups) -> Result<(), ValidationError> {
     for key in map.keys() {
         validate_id(&format!("fileGroups.{}", key), key)?;
@@ -46,6 +59,14 @@ fn validate_tasks(map: &HashMap<String, TaskConfig>) -> Result<(), ValidationErr
     Ok(())
 }
 
+fn validate_tags(list: &[String]) -> Result<(), ValidationError> {
+    for (index, tag) in list.iter().enumerate() {
+        validate_id(&format!("tags[{}]", index), tag)?;
+    }
+
+    Ok(())
+}
+
 fn validate_channel(value: &str) -> Result<(), ValidationError> {
     if !value.is_empty() && !value.starts_with('#') {
         return Err(create_validation_error(
@@ -58,7 +79,7 @@ fn validate_channel(value: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
-#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectLanguage {
     JavaScript,
@@ -72,7 +93,7 @@ impl Default for ProjectLanguage {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProjectType {
     Application,
@@ -124,8 +145,19 @@ pub struct ProjectWorkspaceConfig {
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectConfig {
+    /// An alternate name the project can be referenced by, in targets and commands.
+    #[validate(custom = "validate_alias")]
+    pub alias: Option<String>,
+
     pub depends_on: Vec<ProjectID>,
 
+    /// Default `envFile` for all tasks in the project, when not defined by the task.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_env_file")]
+    #[schemars(schema_with = "make_env_file_schema")]
+    #[validate(custom = "validate_env_file")]
+    pub env_file: Option<Vec<FilePath>>,
+
     #[validate(custom = "validate_file_groups")]
     pub file_groups: FileGroups,
 
@@ -134,6 +166,10 @@ pub struct ProjectConfig {
     #[validate]
     pub project: Option<ProjectMetadataConfig>,
 
+    /// Arbitrary tags for grouping projects, used by `#tag:task` targets.
+    #[validate(custom = "validate_tags")]
+    pub tags: Vec<String>,
+
     #[validate(custom = "validate_tasks")]
     #[validate]
     pub tasks: HashMap<String, TaskConfig>,
@@ -235,6 +271,38 @@ fileGroups:
         });
     }
 
+    mod alias {
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>alias</id>: Must be a valid ID or a scoped package name (@scope/name)."
+        )]
+        fn invalid_value() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "alias: '@acme'")?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn supports_scoped_names() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    "alias: '@acme/ui'",
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(config.alias, Some("@acme/ui".to_owned()));
+
+                Ok(())
+            });
+        }
+    }
+
     mod depends_on {
         #[test]
         #[should_panic(
@@ -251,6 +319,50 @@ fileGroups:
         }
     }
 
+    mod env_file {
+        use moon_utils::string_vec;
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>envFile</id>: Expected a boolean, a string, or a sequence of strings type, received unsigned int `123`."
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "envFile: 123")?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn supports_bool() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "envFile: true")?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(config.env_file, Some(string_vec![".env"]));
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn defaults_to_none() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "fake: value")?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(config.env_file, None);
+
+                Ok(())
+            });
+        }
+    }
+
     mod file_groups {
         #[test]
         #[should_panic(
@@ -286,6 +398,57 @@ fileGroups:
         }
     }
 
+    mod tags {
+        use moon_utils::string_vec;
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>tags</id>: Expected a sequence type, received string \"abc\"."
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "tags: abc")?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>tags[1]</id>: Must be a valid ID. Accepts A-Z, a-z, 0-9, - (dashes), _ (underscores), and must start with a letter."
+        )]
+        fn invalid_value() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    "tags: ['frontend', '#backend']",
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn loads_list() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    "tags: ['frontend', 'react']",
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(config.tags, string_vec!["frontend", "react"]);
+
+                Ok(())
+            });
+        }
+    }
+
     mod tasks {
         use super::*;
 
@@ -400,6 +563,27 @@ tasks:
                 Ok(())
             });
         }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid matrix value")]
+        fn invalid_matrix_value() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    r#"
+tasks:
+    test:
+        command: jest
+        matrix:
+            node: ['16 || 18']
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
     }
 
     mod project {
@@ -1,10 +1,12 @@
-use crate::types::{FilePath, FilePathOrGlob, TargetID};
-use crate::validators::{validate_child_or_root_path, validate_target};
+use crate::errors::create_validation_error;
+use crate::types::{FilePath, FilePathOrGlob, TargetID, TaskID};
+use crate::validators::{validate_child_or_root_path, validate_id, validate_target};
 use moon_utils::process::split_args;
+use moon_utils::regex::matches_matrix_value;
 use schemars::gen::SchemaGenerator;
 use schemars::schema::Schema;
 use schemars::{schema_for, JsonSchema};
-use serde::de::{self, SeqAccess};
+use serde::de::{self, MapAccess, SeqAccess};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -13,14 +15,20 @@ use validator::{Validate, ValidationError};
 // These structs utilize optional fields so that we can handle merging effectively,
 // as we need a way to skip "undefined" values. So don't use serde defaults here.
 
-fn validate_deps(list: &[String]) -> Result<(), ValidationError> {
+fn validate_deps(list: &[TaskDependencyConfig]) -> Result<(), ValidationError> {
     for (index, item) in list.iter().enumerate() {
-        validate_target(&format!("deps[{}]", index), item)?;
+        validate_target(&format!("deps[{}]", index), &item.target)?;
     }
 
     Ok(())
 }
 
+fn validate_extends(value: &str) -> Result<(), ValidationError> {
+    validate_id("extends", value)?;
+
+    Ok(())
+}
+
 fn validate_inputs(list: &[String]) -> Result<(), ValidationError> {
     for (index, item) in list.iter().enumerate() {
         validate_child_or_root_path(&format!("inputs[{}]", index), item)?;
@@ -37,6 +45,50 @@ fn validate_outputs(list: &[String]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_matrix(matrix: &HashMap<String, Vec<String>>) -> Result<(), ValidationError> {
+    for (name, values) in matrix {
+        let key = format!("matrix.{}", name);
+
+        validate_id(&key, name)?;
+
+        if values.is_empty() {
+            return Err(create_validation_error(
+                "required_matrix_values",
+                &key,
+                String::from("Must define at least 1 value."),
+            ));
+        }
+
+        for (index, value) in values.iter().enumerate() {
+            if !matches_matrix_value(value) {
+                return Err(create_validation_error(
+                    "invalid_matrix_value",
+                    &format!("{}[{}]", key, index),
+                    String::from("Must be a valid matrix value, as it's used in target IDs. Accepts A-Z, a-z, 0-9, - (dashes), _ (underscores), and . (periods)."),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_mutex(list: &[String]) -> Result<(), ValidationError> {
+    for (index, item) in list.iter().enumerate() {
+        validate_id(&format!("mutex[{}]", index), item)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn validate_env_file(list: &[String]) -> Result<(), ValidationError> {
+    for (index, item) in list.iter().enumerate() {
+        validate_child_or_root_path(&format!("envFile[{}]", index), item)?;
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
@@ -67,6 +119,12 @@ impl Default for TaskMergeStrategy {
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskOptionsConfig {
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_env_file")]
+    #[schemars(schema_with = "make_env_file_schema")]
+    #[validate(custom = "validate_env_file")]
+    pub env_file: Option<Vec<FilePath>>,
+
     pub merge_args: Option<TaskMergeStrategy>,
 
     pub merge_deps: Option<TaskMergeStrategy>,
@@ -77,6 +135,9 @@ pub struct TaskOptionsConfig {
 
     pub merge_outputs: Option<TaskMergeStrategy>,
 
+    #[validate(custom = "validate_mutex")]
+    pub mutex: Option<Vec<String>>,
+
     pub retry_count: Option<u8>,
 
     #[serde(rename = "runInCI")]
@@ -88,11 +149,13 @@ pub struct TaskOptionsConfig {
 impl Default for TaskOptionsConfig {
     fn default() -> Self {
         TaskOptionsConfig {
+            env_file: None,
             merge_args: Some(TaskMergeStrategy::default()),
             merge_deps: Some(TaskMergeStrategy::default()),
             merge_env: Some(TaskMergeStrategy::default()),
             merge_inputs: Some(TaskMergeStrategy::default()),
             merge_outputs: Some(TaskMergeStrategy::default()),
+            mutex: None,
             retry_count: Some(0),
             run_in_ci: Some(true),
             run_from_workspace_root: Some(false),
@@ -100,6 +163,34 @@ impl Default for TaskOptionsConfig {
     }
 }
 
+// Dependencies can be declared as a target string, or as an object with args and
+// env vars that are passed to the dependency when it runs. For example:
+//   deps: ['app:build', { target: 'codegen:run', args: '--mode=prod' }]
+// Objects may also be marked as `optional` (ignored when the target does not exist),
+// or `after` (only enforces ordering when the target is already being ran).
+#[derive(Clone, Debug, Default, JsonSchema, PartialEq, Serialize)]
+pub struct TaskDependencyConfig {
+    pub after: Option<bool>,
+
+    #[schemars(schema_with = "make_args_schema")]
+    pub args: Option<Vec<String>>,
+
+    pub env: Option<HashMap<String, String>>,
+
+    pub optional: Option<bool>,
+
+    pub target: TargetID,
+}
+
+impl TaskDependencyConfig {
+    pub fn new(target: &str) -> Self {
+        TaskDependencyConfig {
+            target: target.to_owned(),
+            ..TaskDependencyConfig::default()
+        }
+    }
+}
+
 // We use serde(default) here because figment *does not* apply defaults
 // for structs nested within collections. Primarily hash maps.
 #[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
@@ -111,14 +202,24 @@ pub struct TaskConfig {
 
     pub command: Option<String>,
 
+    #[schemars(schema_with = "make_deps_schema")]
     #[validate(custom = "validate_deps")]
-    pub deps: Option<Vec<TargetID>>,
+    pub deps: Option<Vec<TaskDependencyConfig>>,
 
     pub env: Option<HashMap<String, String>>,
 
+    /// Another task ID in the project to inherit and merge fields from.
+    #[validate(custom = "validate_extends")]
+    pub extends: Option<TaskID>,
+
     #[validate(custom = "validate_inputs")]
     pub inputs: Option<Vec<FilePathOrGlob>>,
 
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_matrix")]
+    #[validate(custom = "validate_matrix")]
+    pub matrix: Option<HashMap<String, Vec<String>>>,
+
     #[serde(default)]
     #[validate]
     pub options: TaskOptionsConfig,
@@ -173,6 +274,201 @@ where
     Ok(Some(deserializer.deserialize_any(DeserializeArgs)?))
 }
 
+// Matrix values are always strings, but YAML authors will write
+// numbers and booleans (`node: [14, 16]`), so convert them.
+struct DeserializeMatrixValue;
+
+impl<'de> de::Visitor<'de> for DeserializeMatrixValue {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string, number, or boolean")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_string())
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_string())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_string())
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_string())
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.to_owned())
+    }
+}
+
+struct MatrixValue(String);
+
+impl<'de> Deserialize<'de> for MatrixValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(MatrixValue(
+            deserializer.deserialize_any(DeserializeMatrixValue)?,
+        ))
+    }
+}
+
+fn deserialize_matrix<'de, D>(
+    deserializer: D,
+) -> Result<Option<HashMap<String, Vec<String>>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let matrix = HashMap::<String, Vec<MatrixValue>>::deserialize(deserializer)?;
+
+    Ok(Some(
+        matrix
+            .into_iter()
+            .map(|(name, values)| (name, values.into_iter().map(|value| value.0).collect()))
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+struct TaskDependencyObject {
+    after: Option<bool>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_args")]
+    args: Option<Vec<String>>,
+
+    env: Option<HashMap<String, String>>,
+
+    optional: Option<bool>,
+
+    target: TargetID,
+}
+
+struct DeserializeDependency;
+
+impl<'de> de::Visitor<'de> for DeserializeDependency {
+    type Value = TaskDependencyConfig;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or object")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(TaskDependencyConfig::new(value))
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let object = TaskDependencyObject::deserialize(de::value::MapAccessDeserializer::new(map))?;
+
+        Ok(TaskDependencyConfig {
+            after: object.after,
+            args: object.args,
+            env: object.env,
+            optional: object.optional,
+            target: object.target,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskDependencyConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeserializeDependency)
+    }
+}
+
+struct DeserializeEnvFile;
+
+impl<'de> de::Visitor<'de> for DeserializeEnvFile {
+    type Value = Option<Vec<String>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean, a string, or a sequence of strings")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // true = ".env" in the project root, false = disabled
+        Ok(Some(if value {
+            vec![String::from(".env")]
+        } else {
+            vec![]
+        }))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let mut vec = Vec::new();
+
+        while let Some(elem) = visitor.next_element()? {
+            vec.push(elem);
+        }
+
+        Ok(Some(vec))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Some(vec![value.to_owned()]))
+    }
+}
+
+pub(crate) fn deserialize_env_file<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(DeserializeEnvFile)
+}
+
 // JSON SCHEMA
 
 #[derive(JsonSchema)]
@@ -190,6 +486,38 @@ fn make_args_schema(_gen: &mut SchemaGenerator) -> Schema {
     Schema::Object(root.schema)
 }
 
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum DepsField {
+    #[allow(dead_code)]
+    String(String),
+    #[allow(dead_code)]
+    Object(TaskDependencyConfig),
+}
+
+fn make_deps_schema(_gen: &mut SchemaGenerator) -> Schema {
+    let root = schema_for!(Vec<DepsField>);
+
+    Schema::Object(root.schema)
+}
+
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum EnvFileField {
+    #[allow(dead_code)]
+    Bool(bool),
+    #[allow(dead_code)]
+    String(String),
+    #[allow(dead_code)]
+    Sequence(Vec<String>),
+}
+
+pub(crate) fn make_env_file_schema(_gen: &mut SchemaGenerator) -> Schema {
+    let root = schema_for!(EnvFileField);
+
+    Schema::Object(root.schema)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,6 +683,10 @@ args: 'arg -o @token(0) --opt value "quoted arg"'
     }
 
     mod deps {
+        use super::{TaskConfig, TaskDependencyConfig};
+        use moon_utils::string_vec;
+        use std::collections::HashMap;
+
         #[test]
         #[should_panic(
             expected = "Invalid field <id>deps</id>: Expected a sequence type, received string \"abc\"."
@@ -377,7 +709,7 @@ deps: abc
 
         #[test]
         #[should_panic(
-            expected = "Invalid field <id>deps.0</id>: Expected a string type, received unsigned int `123`."
+            expected = "Invalid field <id>deps.0</id>: Expected a string or object type, received unsigned int `123`."
         )]
         fn invalid_value_type() {
             figment::Jail::expect_with(|jail| {
@@ -396,6 +728,102 @@ deps:
             });
         }
 
+        #[test]
+        fn supports_strings_and_objects() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+deps:
+    - 'app:build'
+    - target: 'codegen:run'
+      args: '--mode prod'
+      env:
+        NODE_ENV: production
+"#,
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(
+                    config,
+                    TaskConfig {
+                        command: Some(String::from("foo")),
+                        deps: Some(vec![
+                            TaskDependencyConfig::new("app:build"),
+                            TaskDependencyConfig {
+                                args: Some(string_vec!["--mode", "prod"]),
+                                env: Some(HashMap::from([(
+                                    "NODE_ENV".to_owned(),
+                                    "production".to_owned()
+                                )])),
+                                target: String::from("codegen:run"),
+                                ..TaskDependencyConfig::default()
+                            }
+                        ]),
+                        ..TaskConfig::default()
+                    }
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn supports_optional_and_after() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+deps:
+    - target: '^:build'
+      optional: true
+    - target: 'app:lint'
+      after: true
+"#,
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(
+                    config.deps,
+                    Some(vec![
+                        TaskDependencyConfig {
+                            optional: Some(true),
+                            ..TaskDependencyConfig::new("^:build")
+                        },
+                        TaskDependencyConfig {
+                            after: Some(true),
+                            ..TaskDependencyConfig::new("app:lint")
+                        }
+                    ])
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Invalid field <id>deps.0</id>: Missing field <id>target</id>.")]
+        fn requires_target_in_objects() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+deps:
+    - args: '--mode prod'
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
         //         #[test]
         //         #[should_panic(
         //             expected = "Invalid field <id>deps.0</id>: Expected a string type, received unsigned int `123`."
@@ -504,6 +932,123 @@ inputs:
         }
     }
 
+    mod matrix {
+        use super::TaskConfig;
+        use moon_utils::string_vec;
+        use std::collections::HashMap;
+        use validator::Validate;
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>matrix</id>: Expected a map type, received string \"abc\"."
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+matrix: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn converts_scalars_to_strings() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+matrix:
+  node: [14, 16.5]
+  browser: [chrome, firefox]
+  coverage: [true]
+"#,
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(
+                    config.matrix,
+                    Some(HashMap::from([
+                        ("node".to_owned(), string_vec!["14", "16.5"]),
+                        ("browser".to_owned(), string_vec!["chrome", "firefox"]),
+                        ("coverage".to_owned(), string_vec!["true"]),
+                    ]))
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn requires_values() {
+            let config = TaskConfig {
+                matrix: Some(HashMap::from([("node".to_owned(), vec![])])),
+                ..TaskConfig::default()
+            };
+
+            assert!(config.validate().is_err());
+        }
+
+        #[test]
+        fn requires_valid_values() {
+            let config = TaskConfig {
+                matrix: Some(HashMap::from([("node".to_owned(), string_vec!["1 2"])])),
+                ..TaskConfig::default()
+            };
+
+            assert!(config.validate().is_err());
+        }
+
+        #[test]
+        fn requires_values_valid_in_targets() {
+            for value in ["16 || 18", "chrome/latest", "a=b", "a,b", "[a]"] {
+                let config = TaskConfig {
+                    matrix: Some(HashMap::from([("node".to_owned(), string_vec![value])])),
+                    ..TaskConfig::default()
+                };
+
+                assert!(config.validate().is_err(), "{} should be invalid", value);
+            }
+        }
+    }
+
+    mod extends {
+        use super::TaskConfig;
+        use validator::Validate;
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>extends</id>: Expected a string type, received unsigned int `123`."
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::CONFIG_FILENAME, "extends: 123")?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn requires_valid_id() {
+            let config = TaskConfig {
+                extends: Some("base task".to_owned()),
+                ..TaskConfig::default()
+            };
+
+            assert!(config.validate().is_err());
+        }
+    }
+
     mod outputs {
         #[test]
         #[should_panic(
@@ -609,6 +1154,88 @@ options:
             });
         }
 
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>options.envFile</id>: Expected a boolean, a string, or a sequence of strings type, received unsigned int `123`."
+        )]
+        fn invalid_env_file_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    envFile: 123
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn supports_env_file_variants() {
+            use moon_utils::string_vec;
+
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::CONFIG_FILENAME, "options:\n  envFile: true")?;
+
+                assert_eq!(
+                    super::load_jailed_config()?.options.env_file,
+                    Some(string_vec![".env"])
+                );
+
+                jail.create_file(super::CONFIG_FILENAME, "options:\n  envFile: false")?;
+
+                assert_eq!(
+                    super::load_jailed_config()?.options.env_file,
+                    Some(string_vec![])
+                );
+
+                jail.create_file(super::CONFIG_FILENAME, "options:\n  envFile: .env.local")?;
+
+                assert_eq!(
+                    super::load_jailed_config()?.options.env_file,
+                    Some(string_vec![".env.local"])
+                );
+
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    "options:\n  envFile:\n    - .env\n    - /.env.shared",
+                )?;
+
+                assert_eq!(
+                    super::load_jailed_config()?.options.env_file,
+                    Some(string_vec![".env", "/.env.shared"])
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>options.mutex</id>: Expected a sequence type, received unsigned int `123`."
+        )]
+        fn invalid_mutex_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    mutex: 123
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
         #[test]
         #[should_panic(
             expected = "Invalid field <id>options.retryCount</id>: Expected u8 type, received string \"abc\"."
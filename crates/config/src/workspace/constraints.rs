@@ -0,0 +1,34 @@
+use crate::project::ProjectType;
+use crate::validators::validate_id;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use validator::{Validate, ValidationError};
+
+fn validate_forbidden_tag_dependencies(
+    map: &HashMap<String, Vec<String>>,
+) -> Result<(), ValidationError> {
+    for (tag, forbidden_tags) in map {
+        let key = format!("constraints.forbiddenTagDependencies.{}", tag);
+
+        validate_id(&key, tag)?;
+
+        for (index, forbidden_tag) in forbidden_tags.iter().enumerate() {
+            validate_id(&format!("{}[{}]", key, index), forbidden_tag)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintsConfig {
+    /// Types of projects that may be depended on. All types are allowed when empty.
+    pub dependency_types: Vec<ProjectType>,
+
+    /// Map of project tags to tags of projects they may not depend on.
+    #[validate(custom = "validate_forbidden_tag_dependencies")]
+    pub forbidden_tag_dependencies: HashMap<String, Vec<String>>,
+}
@@ -1,5 +1,6 @@
 // .moon/workspace.yml
 
+mod constraints;
 pub mod node;
 mod typescript;
 mod vcs;
@@ -9,6 +10,7 @@ use crate::errors::map_figment_error_to_validation_errors;
 use crate::providers::url::Url;
 use crate::types::{FileGlob, FilePath};
 use crate::validators::{validate_child_relative_path, validate_extends, validate_id};
+pub use constraints::ConstraintsConfig;
 use figment::value::{Dict, Map};
 use figment::{
     providers::{Format, Serialized, Yaml},
@@ -76,6 +78,9 @@ pub struct WorkspaceConfig {
     #[validate]
     pub action_runner: ActionRunnerConfig,
 
+    #[validate]
+    pub constraints: ConstraintsConfig,
+
     #[validate(custom = "validate_extends")]
     pub extends: Option<String>,
 
@@ -275,6 +280,7 @@ mod tests {
                 config,
                 WorkspaceConfig {
                     action_runner: ActionRunnerConfig::default(),
+                    constraints: ConstraintsConfig::default(),
                     extends: None,
                     node: NodeConfig::default(),
                     projects: HashMap::new(),
@@ -288,6 +294,81 @@ mod tests {
         });
     }
 
+    mod constraints {
+        use super::*;
+        use crate::project::ProjectType;
+        use moon_utils::string_vec;
+
+        #[test]
+        fn loads_types_and_tags() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    dependencyTypes: [library, tool]
+    forbiddenTagDependencies:
+        mobile: [web]"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.constraints,
+                    ConstraintsConfig {
+                        dependency_types: vec![ProjectType::Library, ProjectType::Tool],
+                        forbidden_tag_dependencies: HashMap::from([(
+                            "mobile".to_owned(),
+                            string_vec!["web"]
+                        )]),
+                    }
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Unknown option <id>app</id>.")]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    dependencyTypes: [app]"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "Invalid field <id>constraints.forbiddenTagDependencies.mobile[0]</id>: Must be a valid ID."
+        )]
+        fn invalid_tag() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    forbiddenTagDependencies:
+        mobile: ['#web']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
     mod extends {
         use super::*;
         use std::fs;
@@ -467,6 +548,7 @@ node:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        constraints: ConstraintsConfig::default(),
                         extends: None,
                         node: NodeConfig {
                             package_manager: PackageManager::Yarn,
@@ -972,6 +1054,7 @@ vcs:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        constraints: ConstraintsConfig::default(),
                         extends: None,
                         node: NodeConfig::default(),
                         projects: HashMap::new(),
@@ -12,6 +12,9 @@ pub enum MoonError {
     #[error("File system failure for <path>{0}</path>: {1}")]
     FileSystem(PathBuf, #[source] IoError),
 
+    #[error("Failed to parse <path>{0}</path> on line {1}: {2}")]
+    DotEnv(PathBuf, usize, String),
+
     #[error("Failed to create a hard link from <path>{0}</path> to <path>{1}</path>.")]
     HardLink(PathBuf, PathBuf),
 
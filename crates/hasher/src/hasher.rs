@@ -21,9 +21,15 @@ pub struct TargetHasher {
     // Environment variables
     env_vars: BTreeMap<String, String>,
 
+    // Input environment variables mapped to their current value
+    input_env_vars: BTreeMap<String, String>,
+
     // Input files and globs mapped to a unique hash
     input_hashes: BTreeMap<String, String>,
 
+    // Task `matrix` values of a variant
+    matrix: BTreeMap<String, String>,
+
     // Node.js version
     node_version: String,
 
@@ -42,6 +48,9 @@ pub struct TargetHasher {
     // Tash `target`
     target: String,
 
+    // Runtime token variables, excluding volatile ones
+    token_vars: BTreeMap<String, String>,
+
     // `tsconfig.json` `compilerOptions`
     tsconfig_compiler_options: BTreeMap<String, String>,
 
@@ -70,6 +79,11 @@ impl TargetHasher {
         }
     }
 
+    /// Hash a mapping of input environment variable names to their current values.
+    pub fn hash_input_env_vars(&mut self, env_vars: BTreeMap<String, String>) {
+        self.input_env_vars.extend(env_vars);
+    }
+
     /// Hash a mapping of input file paths to unique file hashes.
     /// File paths *must* be relative from the workspace root.
     pub fn hash_inputs(&mut self, inputs: BTreeMap<String, String>) {
@@ -101,11 +115,12 @@ impl TargetHasher {
         self.project_deps = project.get_dependencies(); // Sorted
     }
 
-    /// Hash `args`, `inputs`, `deps`, and `env` vars from a task.
+    /// Hash `args`, `inputs`, `deps`, `matrix` values, and `env` vars from a task.
     pub fn hash_task(&mut self, task: &Task) {
         self.command = task.command.clone();
         self.args = task.args.clone();
-        self.deps = task.deps.clone();
+        self.deps = task.deps.iter().map(|dep| dep.get_id()).collect();
+        self.matrix = task.matrix_values.clone();
         self.target = task.target.clone();
 
         // Sort vectors to be deterministic
@@ -113,6 +128,12 @@ impl TargetHasher {
         self.deps.sort();
     }
 
+    /// Hash the values of runtime token variables referenced by the task,
+    /// as they are resolved after the task has been hashed.
+    pub fn hash_token_vars(&mut self, token_vars: BTreeMap<String, String>) {
+        self.token_vars.extend(token_vars);
+    }
+
     /// Hash `tsconfig.json` compiler options that may alter compiled/generated output.
     pub fn hash_tsconfig_json(&mut self, tsconfig: &TsConfigJson) {
         if let Some(compiler_options) = &tsconfig.compiler_options {
@@ -156,7 +177,9 @@ impl TargetHasher {
         hash_vec(&self.args, &mut sha);
         hash_vec(&self.deps, &mut sha);
         hash_btree(&self.env_vars, &mut sha);
+        hash_btree(&self.input_env_vars, &mut sha);
         hash_btree(&self.input_hashes, &mut sha);
+        hash_btree(&self.matrix, &mut sha);
 
         // Deps
         hash_vec(&self.project_deps, &mut sha);
@@ -167,6 +190,9 @@ impl TargetHasher {
         // Config
         hash_btree(&self.tsconfig_compiler_options, &mut sha);
 
+        // Tokens
+        hash_btree(&self.token_vars, &mut sha);
+
         format!("{:x}", sha.finalize())
     }
 }
@@ -202,6 +228,39 @@ mod tests {
 
     mod btreemap {
         use super::*;
+        use moon_config::TaskConfig;
+
+        #[test]
+        fn returns_diff_hash_for_diff_matrix_values() {
+            let mut task = Task::from_config("app:test".to_owned(), &TaskConfig::default());
+
+            task.matrix_values = BTreeMap::from([("node".to_owned(), "14".to_owned())]);
+
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_task(&task);
+
+            task.matrix_values = BTreeMap::from([("node".to_owned(), "16".to_owned())]);
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_task(&task);
+
+            assert_ne!(hasher1.to_hash(), hasher2.to_hash());
+        }
+
+        #[test]
+        fn returns_diff_hash_for_diff_env_var_values() {
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1
+                .hash_input_env_vars(BTreeMap::from([("NODE_ENV".to_owned(), "test".to_owned())]));
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_input_env_vars(BTreeMap::from([(
+                "NODE_ENV".to_owned(),
+                "production".to_owned(),
+            )]));
+
+            assert_ne!(hasher1.to_hash(), hasher2.to_hash());
+        }
 
         #[test]
         fn returns_same_hash_for_same_value_inserted() {
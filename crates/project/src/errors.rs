@@ -1,13 +1,31 @@
 use moon_config::constants;
 use moon_error::MoonError;
 use moon_utils::glob::GlobError;
-use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ProjectError {
-    #[error("A dependency cycle has been detected between projects.")]
-    DependencyCycleDetected,
+    #[error("Alias <id>{0}</id> is used by multiple projects, <id>{1}</id> and <id>{2}</id>.")]
+    AliasCollision(String, String, String), // alias, existing project, project
+
+    #[error("Found {0} project alias collision(s).\n\n{1}")]
+    AliasCollisions(usize, String), // count, collisions
+
+    #[error("A dependency cycle has been detected between projects <id>{0}</id>.\n\n{1}")]
+    DependencyCycleDetected(String, String), // cycle, edge origins
+
+    #[error(
+        "Project <id>{0}</id> cannot depend on <id>{1}</id> ({2}), as <symbol>{3}</symbol> projects may not be dependencies."
+    )]
+    ConstraintDependencyType(String, String, String, String), // project, dep, origin, type
+
+    #[error(
+        "Project <id>{0}</id> cannot depend on <id>{1}</id> ({2}), as projects tagged <symbol>{3}</symbol> may not depend on projects tagged <symbol>{4}</symbol>."
+    )]
+    ConstraintForbiddenTag(String, String, String, String, String), // project, dep, origin, tag, dep tag
+
+    #[error("Found {0} project constraint violation(s).\n\n{1}")]
+    ConstraintViolations(usize, String), // count, violations
 
     #[error(
         "Failed to validate <file>{0}/{}</file> configuration file.\n\n{1}",
@@ -15,13 +33,13 @@ pub enum ProjectError {
     )]
     InvalidConfigFile(String, String),
 
-    #[error("No project exists at path <file>{0}</file>.")]
-    MissingProject(String),
-
     #[error(
-        "Task outputs do not support file globs. Found <path>{0}</path> in <target>{1}</target>."
+        "Output <file>{1}</file> of matrix task <target>{0}</target> must reference every <symbol>$matrix</symbol> value, so that variants don't write to the same location."
     )]
-    NoOutputGlob(PathBuf, String),
+    MatrixSharedOutput(String, String), // target, output
+
+    #[error("No project exists at path <file>{0}</file>.")]
+    MissingProject(String),
 
     #[error("No project has been configured with the ID <id>{0}</id>.")]
     UnconfiguredID(String),
@@ -29,6 +47,15 @@ pub enum ProjectError {
     #[error("Task <id>{0}</id> has not been configured for project <id>{1}</id>.")]
     UnconfiguredTask(String, String),
 
+    #[error("A cycle has been detected between extended tasks <id>{0}</id>.")]
+    TaskExtendsCycle(String),
+
+    #[error("A cycle has been detected between file group references <id>{0}</id>.")]
+    FileGroupReferenceCycle(String),
+
+    #[error("Task <id>{0}</id> extends unknown task <id>{1}</id>.")]
+    UnknownExtendedTask(String, String), // task, extends
+
     #[error(transparent)]
     Glob(#[from] GlobError),
 
@@ -57,6 +84,9 @@ pub enum TargetError {
     #[error("Target <target>:</target> encountered. Wildcard project and task not supported.")]
     TooWild,
 
+    #[error("Target <target>{0}</target> did not match any project tasks.")]
+    NoMatchingTasks(String),
+
     #[error(
         "All projects scope (:) is not supported in task deps, for target <target>{0}</target>."
     )]
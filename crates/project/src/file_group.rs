@@ -56,7 +56,7 @@ impl FileGroup {
     }
 
     /// Returns the file group as a list of file globs (as-is),
-    /// relative to the project root.
+    /// relative to the project root. Negated globs retain their leading "!".
     pub fn globs(
         &self,
         workspace_root: &Path,
@@ -65,7 +65,12 @@ impl FileGroup {
         let mut globs = vec![];
 
         for file in &self.files {
-            if glob::is_glob(file) {
+            if let Some(negated) = file.strip_prefix('!') {
+                globs.push(PathBuf::from(format!(
+                    "!{}",
+                    expand_root_path(negated, workspace_root, project_root).to_string_lossy()
+                )));
+            } else if glob::is_glob(file) {
                 globs.push(expand_root_path(file, workspace_root, project_root));
             }
         }
@@ -106,9 +111,36 @@ impl FileGroup {
         project_root: &Path,
     ) -> Result<Vec<PathBuf>, ProjectError> {
         let mut list = vec![];
+        let mut negated_globs = vec![];
 
+        // Negated globs apply to all other files in the group,
+        // so match them against absolute paths
         for file in &self.files {
-            if glob::is_glob(file) {
+            if let Some(negated) = file.strip_prefix('!') {
+                negated_globs.push(glob::normalize(&expand_root_path(
+                    negated,
+                    workspace_root,
+                    project_root,
+                ))?);
+            }
+        }
+
+        let negations = if negated_globs.is_empty() {
+            None
+        } else {
+            Some(glob::GlobSet::new(&negated_globs)?)
+        };
+        let is_negated = |path: &Path| -> Result<bool, ProjectError> {
+            match &negations {
+                Some(set) => Ok(set.matches(path)?),
+                None => Ok(false),
+            }
+        };
+
+        for file in &self.files {
+            if file.starts_with('!') {
+                continue;
+            } else if glob::is_glob(file) {
                 let root = if file.starts_with('/') {
                     workspace_root
                 } else {
@@ -122,7 +154,7 @@ impl FileGroup {
                         path.is_file()
                     };
 
-                    if allowed {
+                    if allowed && !is_negated(&path)? {
                         list.push(path);
                     }
                 }
@@ -135,7 +167,7 @@ impl FileGroup {
                     path.is_file()
                 };
 
-                if allowed {
+                if allowed && !is_negated(&path)? {
                     list.push(path.to_owned());
                 }
             }
@@ -179,6 +211,18 @@ mod tests {
             );
         }
 
+        #[test]
+        fn excludes_negated_dirs() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let file_group = FileGroup::new("id", string_vec!["**/*", "!**/subdir"]);
+
+            assert_eq!(
+                file_group.dirs(&workspace_root, &project_root).unwrap(),
+                vec![project_root.join("dir")]
+            );
+        }
+
         #[test]
         fn doesnt_return_files() {
             let workspace_root = get_fixtures_dir("base");
@@ -228,6 +272,27 @@ mod tests {
             );
         }
 
+        #[test]
+        fn excludes_negated_files() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let file_group = FileGroup::new(
+                "id",
+                string_vec!["**/*.{ts,tsx}", "README.md", "!dir/subdir/**/*", "!*.md"],
+            );
+
+            let mut files = file_group.files(&workspace_root, &project_root).unwrap();
+            files.sort();
+
+            assert_eq!(
+                files,
+                vec![
+                    project_root.join("dir/other.tsx"),
+                    project_root.join("file.ts"),
+                ]
+            );
+        }
+
         #[test]
         fn doesnt_return_dirs() {
             let workspace_root = get_fixtures_dir("base");
@@ -261,6 +326,24 @@ mod tests {
                 ]
             );
         }
+
+        #[test]
+        fn includes_negated_globs() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let file_group = FileGroup::new("id", string_vec!["**/*", "!**/*.test.ts"]);
+
+            assert_eq!(
+                file_group.globs(&workspace_root, &project_root).unwrap(),
+                vec![
+                    project_root.join("**/*"),
+                    PathBuf::from(format!(
+                        "!{}",
+                        project_root.join("**/*.test.ts").to_string_lossy()
+                    )),
+                ]
+            );
+        }
     }
 
     mod root {
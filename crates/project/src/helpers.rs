@@ -3,6 +3,12 @@ use crate::types::ProjectsSourceMap;
 use moon_error::MoonError;
 use moon_logger::{color, warn};
 use moon_utils::{glob, path, regex};
+use petgraph::algo::is_cyclic_directed;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::Direction;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 
 /// Infer a project name from a source path, by using the name of
@@ -17,6 +23,61 @@ pub fn infer_project_name_and_source(source: &str) -> (String, String) {
     }
 }
 
+/// Find the shortest cycle within a directed graph, if one exists. The returned
+/// list of indices starts and ends with the same node, for example `a → b → a`.
+pub fn find_shortest_cycle<N, E>(graph: &DiGraph<N, E>) -> Option<Vec<NodeIndex>> {
+    if !is_cyclic_directed(graph) {
+        return None;
+    }
+
+    let mut shortest: Option<Vec<NodeIndex>> = None;
+
+    for start in graph.node_indices() {
+        // Breadth-first so that the first path back to the start is the shortest
+        let mut parents: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        'search: while let Some(index) = queue.pop_front() {
+            for neighbor in graph.neighbors_directed(index, Direction::Outgoing) {
+                if neighbor == start {
+                    let mut cycle = vec![start];
+                    let mut current = index;
+
+                    while current != start {
+                        cycle.push(current);
+                        current = *parents.get(&current).unwrap();
+                    }
+
+                    cycle.push(start);
+                    cycle.reverse();
+
+                    if shortest.as_ref().map_or(true, |s| cycle.len() < s.len()) {
+                        shortest = Some(cycle);
+                    }
+
+                    break 'search;
+                }
+
+                if let Entry::Vacant(entry) = parents.entry(neighbor) {
+                    entry.insert(index);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    shortest
+}
+
+/// Return the config spelling of an enum value (`typescript`, `application`),
+/// as its `Debug` output is the Rust variant name.
+pub fn enum_to_string<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(string)) => string,
+        _ => String::new(),
+    }
+}
+
 /// For each pattern in the globs list, glob the file system
 /// for potential projects, and infer their name and source.
 pub fn detect_projects_with_globs(
@@ -51,3 +112,80 @@ pub fn detect_projects_with_globs(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod enum_to_string {
+        use super::*;
+        use moon_config::{ProjectLanguage, ProjectType};
+
+        #[test]
+        fn uses_config_spelling() {
+            assert_eq!(enum_to_string(&ProjectLanguage::TypeScript), "typescript");
+            assert_eq!(enum_to_string(&ProjectType::Application), "application");
+        }
+    }
+
+    mod find_shortest_cycle {
+        use super::*;
+
+        #[test]
+        fn returns_none_when_acyclic() {
+            let mut graph: DiGraph<&str, ()> = DiGraph::new();
+            let a = graph.add_node("a");
+            let b = graph.add_node("b");
+            let c = graph.add_node("c");
+
+            graph.add_edge(a, b, ());
+            graph.add_edge(b, c, ());
+            graph.add_edge(a, c, ());
+
+            assert_eq!(find_shortest_cycle(&graph), None);
+        }
+
+        #[test]
+        fn returns_the_minimal_cycle() {
+            let mut graph: DiGraph<&str, ()> = DiGraph::new();
+            let a = graph.add_node("a");
+            let b = graph.add_node("b");
+            let c = graph.add_node("c");
+            let d = graph.add_node("d");
+
+            // a → b → c → d → a, with a shortcut of c → a
+            graph.add_edge(a, b, ());
+            graph.add_edge(b, c, ());
+            graph.add_edge(c, d, ());
+            graph.add_edge(d, a, ());
+            graph.add_edge(c, a, ());
+
+            assert_eq!(find_shortest_cycle(&graph), Some(vec![a, b, c, a]));
+        }
+
+        #[test]
+        fn ignores_nodes_outside_the_cycle() {
+            let mut graph: DiGraph<&str, ()> = DiGraph::new();
+            let root = graph.add_node("root");
+            let a = graph.add_node("a");
+            let b = graph.add_node("b");
+
+            graph.add_edge(root, a, ());
+            graph.add_edge(root, b, ());
+            graph.add_edge(a, b, ());
+            graph.add_edge(b, a, ());
+
+            assert_eq!(find_shortest_cycle(&graph), Some(vec![a, b, a]));
+        }
+
+        #[test]
+        fn supports_self_references() {
+            let mut graph: DiGraph<&str, ()> = DiGraph::new();
+            let a = graph.add_node("a");
+
+            graph.add_edge(a, a, ());
+
+            assert_eq!(find_shortest_cycle(&graph), Some(vec![a, a]));
+        }
+    }
+}
@@ -18,15 +18,18 @@ pub use types::*;
 // Projects
 pub use moon_config::{ProjectID, ProjectType};
 pub use project::{Project, ProjectsMap};
-pub use project_graph::ProjectGraph;
+pub use project_graph::{format_dependency_origin, DependencySource, ProjectGraph};
 
 // Tasks & targets
 pub use moon_config::{TargetID, TaskID, TaskType};
-pub use target::{Target, TargetProject};
-pub use task::{Task, TaskOptions};
+pub use target::{Target, TargetProject, TargetTask};
+pub use task::{Task, TaskDependency, TaskOptions};
 
 // Tokens
-pub use token::{ResolverType, TokenResolver, TokenSharedData, TokenType};
+pub use token::{
+    resolve_runtime_vars, ResolverType, TokenResolver, TokenRuntimeData, TokenSharedData,
+    TokenType, RUNTIME_TOKEN_VARS, VOLATILE_TOKEN_VARS,
+};
 
 // File groups
 pub use file_group::FileGroup;
@@ -1,15 +1,21 @@
-use crate::errors::ProjectError;
+use crate::errors::{ProjectError, TokenError};
 use crate::file_group::FileGroup;
 use crate::target::Target;
 use crate::task::Task;
 use crate::token::{TokenResolver, TokenSharedData};
+use crate::types::{ProjectsSourceMap, TouchedFilePaths};
 use moon_config::constants::CONFIG_PROJECT_FILENAME;
 use moon_config::package::PackageJson;
 use moon_config::tsconfig::TsConfigJson;
-use moon_config::{format_errors, FilePath, GlobalProjectConfig, ProjectConfig, ProjectID, TaskID};
+use moon_config::{
+    format_errors, FilePath, GlobalProjectConfig, ProjectConfig, ProjectID, ProjectLanguage,
+    ProjectType, TaskConfig, TaskID,
+};
 use moon_logger::{color, debug, trace, Logable};
-use moon_utils::path;
+use moon_utils::{path, regex};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
@@ -20,7 +26,7 @@ pub type ProjectsMap = HashMap<ProjectID, Project>;
 pub type TasksMap = HashMap<TaskID, Task>;
 
 // project.yml
-fn load_project_config(
+pub(crate) fn load_project_config(
     log_target: &str,
     project_root: &Path,
     project_source: &str,
@@ -89,6 +95,221 @@ fn create_file_groups_from_config(
     file_groups
 }
 
+fn scope_global_config(
+    global_config: &GlobalProjectConfig,
+    config: &Option<ProjectConfig>,
+) -> GlobalProjectConfig {
+    match config {
+        Some(cfg) => global_config.scoped_to(&cfg.language, &cfg.type_of),
+        None => global_config.scoped_to(&ProjectLanguage::default(), &ProjectType::default()),
+    }
+}
+
+/// Re-root a file pattern from another project so that it's relative
+/// to the workspace root, while preserving negation.
+fn reroot_file_pattern(pattern: &str, project_source: &str) -> String {
+    let (prefix, file) = match pattern.strip_prefix('!') {
+        Some(negated) => ("!", negated),
+        None => ("", pattern),
+    };
+
+    if file.starts_with('/') {
+        return pattern.to_owned();
+    }
+
+    format!(
+        "{}/{}/{}",
+        prefix,
+        project_source.trim_end_matches('/'),
+        file
+    )
+}
+
+struct FileGroupRefContext<'a> {
+    /// Unresolved file groups of each referenced project, so that
+    /// a project's config is only loaded once while resolving.
+    file_groups: RefCell<HashMap<ProjectID, FileGroupsMap>>,
+    global_config: &'a GlobalProjectConfig,
+    log_target: &'a str,
+    projects: &'a ProjectsSourceMap,
+    workspace_root: &'a Path,
+}
+
+/// Load the files of a file group in another project, resolving any of its
+/// own references first, and re-root them to the workspace root.
+fn load_referenced_file_group(
+    context: &FileGroupRefContext,
+    token: &str,
+    project_id: &str,
+    group_id: &str,
+    chain: &[String],
+) -> Result<Vec<String>, ProjectError> {
+    let key = format!("{}:{}", project_id, group_id);
+
+    if chain.contains(&key) {
+        let mut cycle = chain.to_vec();
+        cycle.push(key);
+
+        return Err(ProjectError::FileGroupReferenceCycle(
+            cycle.join("</id> → <id>"),
+        ));
+    }
+
+    let source = match context.projects.get(project_id) {
+        Some(source) => source,
+        None => return Err(ProjectError::UnconfiguredID(project_id.to_owned())),
+    };
+
+    debug!(
+        target: context.log_target,
+        "Resolving file group reference {}",
+        color::id(token),
+    );
+
+    let mut file_group = {
+        let mut cache = context.file_groups.borrow_mut();
+
+        let file_groups = match cache.entry(project_id.to_owned()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let root = context
+                    .workspace_root
+                    .join(path::normalize_separators(source));
+                let config = load_project_config(context.log_target, &root, source)?;
+                let global_config = scope_global_config(context.global_config, &config);
+
+                entry.insert(create_file_groups_from_config(
+                    context.log_target,
+                    &config,
+                    &global_config,
+                ))
+            }
+        };
+
+        match file_groups.get(group_id) {
+            Some(group) => group.clone(),
+            None => {
+                return Err(ProjectError::Token(TokenError::UnknownFileGroup(
+                    token.to_owned(),
+                    group_id.to_owned(),
+                )))
+            }
+        }
+    };
+
+    resolve_file_group_references(context, &mut file_group, project_id, chain)?;
+
+    Ok(file_group
+        .files
+        .iter()
+        .map(|file| reroot_file_pattern(file, source))
+        .collect())
+}
+
+/// Replace `@group(project:groupName)` references in a file group
+/// with the files of the referenced group.
+fn resolve_file_group_references(
+    context: &FileGroupRefContext,
+    file_group: &mut FileGroup,
+    project_id: &str,
+    chain: &[String],
+) -> Result<(), ProjectError> {
+    if !file_group
+        .files
+        .iter()
+        .any(|file| regex::FILE_GROUP_REF_PATTERN.is_match(file))
+    {
+        return Ok(());
+    }
+
+    let mut chain = chain.to_vec();
+    chain.push(format!("{}:{}", project_id, file_group.id));
+
+    let mut files = vec![];
+
+    for file in &file_group.files {
+        match regex::FILE_GROUP_REF_PATTERN.captures(file) {
+            Some(matches) => {
+                files.extend(load_referenced_file_group(
+                    context,
+                    file,
+                    matches.get(1).unwrap().as_str(),
+                    matches.get(2).unwrap().as_str(),
+                    &chain,
+                )?);
+            }
+            None => {
+                files.push(file.to_owned());
+            }
+        }
+    }
+
+    file_group.files = files;
+
+    Ok(())
+}
+
+type ExtendedTasksMap<'cfg> = HashMap<TaskID, (TaskID, Vec<&'cfg TaskConfig>)>;
+
+/// Create a task that extends another task (which is resolved first), by cloning
+/// the extended task and merging the task's configs over it, in order.
+fn resolve_extended_task(
+    log_target: &str,
+    project_id: &str,
+    task_id: &str,
+    extended: &ExtendedTasksMap,
+    tasks: &mut TasksMap,
+    chain: &mut Vec<TaskID>,
+) -> Result<(), ProjectError> {
+    if tasks.contains_key(task_id) {
+        return Ok(());
+    }
+
+    let (extends_id, configs) = extended.get(task_id).unwrap();
+
+    chain.push(task_id.to_owned());
+
+    if chain.contains(extends_id) {
+        chain.push(extends_id.to_owned());
+
+        return Err(ProjectError::TaskExtendsCycle(chain.join("</id> → <id>")));
+    }
+
+    if extended.contains_key(extends_id) {
+        resolve_extended_task(log_target, project_id, extends_id, extended, tasks, chain)?;
+    }
+
+    let mut task = match tasks.get(extends_id) {
+        Some(extended_task) => extended_task.clone(),
+        None => {
+            return Err(ProjectError::UnknownExtendedTask(
+                task_id.to_owned(),
+                extends_id.to_owned(),
+            ))
+        }
+    };
+
+    trace!(
+        target: log_target,
+        "Task {} extends task {}",
+        color::id(task_id),
+        color::id(extends_id)
+    );
+
+    task.target = Target::format(project_id, task_id)?;
+    task.log_target = format!("moon:project:{}", task.target);
+
+    for config in configs {
+        task.merge(config);
+    }
+
+    tasks.insert(task_id.to_owned(), task);
+    chain.pop();
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn create_tasks_from_config(
     log_target: &str,
     config: &Option<ProjectConfig>,
@@ -97,9 +318,12 @@ fn create_tasks_from_config(
     project_root: &Path,
     project_id: &str,
     file_groups: &FileGroupsMap,
+    inferred_dependencies: &[ProjectID],
 ) -> Result<TasksMap, ProjectError> {
     let mut tasks = HashMap::<String, Task>::new();
+    let mut extended: ExtendedTasksMap = HashMap::new();
     let mut depends_on = vec![];
+    let mut env_file = None;
 
     debug!(target: log_target, "Creating tasks");
 
@@ -111,6 +335,7 @@ fn create_tasks_from_config(
 
     if let Some(local_config) = config {
         depends_on.extend(local_config.depends_on.clone());
+        env_file = local_config.env_file.clone();
         rename = local_config.workspace.inherited_tasks.rename.clone();
 
         if let Some(include_config) = &local_config.workspace.inherited_tasks.include {
@@ -123,6 +348,13 @@ fn create_tasks_from_config(
         }
     }
 
+    // Inferred dependencies are also expanded for `^:` task deps
+    for dep_id in inferred_dependencies {
+        if !depends_on.contains(dep_id) {
+            depends_on.push(dep_id.to_owned());
+        }
+    }
+
     // Add global tasks first while taking inheritance config into account
     for (task_id, task_config) in &global_config.tasks {
         // None = Include all
@@ -174,16 +406,40 @@ fn create_tasks_from_config(
             task_id
         };
 
-        tasks.insert(
-            task_name.to_owned(),
-            Task::from_config(Target::format(project_id, task_name)?, task_config),
-        );
+        // Extended tasks are resolved after all other tasks have been created
+        if let Some(extends_id) = &task_config.extends {
+            extended.insert(
+                task_name.to_owned(),
+                (extends_id.to_owned(), vec![task_config]),
+            );
+        } else {
+            tasks.insert(
+                task_name.to_owned(),
+                Task::from_config(Target::format(project_id, task_name)?, task_config),
+            );
+        }
     }
 
     // Add local tasks second
     if let Some(local_config) = config {
         for (task_id, task_config) in &local_config.tasks {
-            if tasks.contains_key(task_id) {
+            if let Some(extends_id) = &task_config.extends {
+                // Extending replaces the global task of the same name
+                tasks.remove(task_id);
+                extended.insert(
+                    task_id.to_owned(),
+                    (extends_id.to_owned(), vec![task_config]),
+                );
+            } else if let Some((_, configs)) = extended.get_mut(task_id) {
+                debug!(
+                    target: log_target,
+                    "Merging task {} with global config",
+                    color::id(task_id)
+                );
+
+                // Global task extends another, so merge after it's resolved
+                configs.push(task_config);
+            } else if tasks.contains_key(task_id) {
                 debug!(
                     target: log_target,
                     "Merging task {} with global config",
@@ -202,21 +458,68 @@ fn create_tasks_from_config(
         }
     }
 
+    let mut extended_ids = extended.keys().cloned().collect::<Vec<TaskID>>();
+    extended_ids.sort();
+
+    for task_id in extended_ids {
+        resolve_extended_task(
+            log_target,
+            project_id,
+            &task_id,
+            &extended,
+            &mut tasks,
+            &mut vec![],
+        )?;
+    }
+
+    // Fan out matrix tasks into a variant per combination of values
+    let mut variants = vec![];
+
+    for (task_id, task) in tasks.iter_mut() {
+        if task.matrix.is_empty() {
+            continue;
+        }
+
+        for (variant_id, variant) in task.create_variants(task_id)? {
+            trace!(
+                target: log_target,
+                "Creating matrix variant {} for task {}",
+                color::id(&variant_id),
+                color::id(task_id)
+            );
+
+            task.variants.push(variant_id.clone());
+            variants.push((variant_id, variant));
+        }
+
+        // The base task is never ran, so its outputs only exist per variant
+        task.outputs.clear();
+    }
+
+    tasks.extend(variants);
+
     // Expand deps, args, inputs, and outputs after all tasks have been created
     for task in tasks.values_mut() {
-        let data = TokenSharedData::new(file_groups, workspace_root, project_root);
+        let data = TokenSharedData::new(file_groups, workspace_root, project_root, config);
 
         debug!(
             target: &task.log_target,
-            "Expanding deps, inputs, outputs, and args",
+            "Expanding deps, inputs, outputs, env, args, and command",
         );
 
+        // Inherit the project-level env file when the task has not defined one
+        if task.options.env_file.is_none() {
+            task.options.env_file = env_file.clone();
+        }
+
         task.expand_deps(project_id, &depends_on)?;
         task.expand_inputs(TokenResolver::for_inputs(&data))?;
         task.expand_outputs(TokenResolver::for_outputs(&data))?;
 
-        // Must be last as it references inputs/outputs
+        // Must be last as they reference inputs/outputs
+        task.expand_env(TokenResolver::for_env(&data))?;
         task.expand_args(TokenResolver::for_args(&data))?;
+        task.expand_command(TokenResolver::for_args(&data))?;
     }
 
     Ok(tasks)
@@ -234,6 +537,11 @@ pub struct Project {
     /// Unique ID for the project. Is the LHS of the `projects` setting.
     pub id: ProjectID,
 
+    /// Project IDs this project implicitly depends on, as inferred by the project graph
+    /// from `package.json` dependencies, or `tsconfig.json` references and paths.
+    /// Does not include IDs listed in `dependsOn`.
+    pub inferred_dependencies: Vec<ProjectID>,
+
     /// Logging target label.
     #[serde(skip)]
     pub log_target: String,
@@ -260,6 +568,20 @@ impl Project {
         source: &str,
         workspace_root: &Path,
         global_config: &GlobalProjectConfig,
+        projects: &ProjectsSourceMap,
+    ) -> Result<Project, ProjectError> {
+        Project::create(id, source, workspace_root, global_config, projects, &[])
+    }
+
+    /// Create a project that also depends on the provided inferred project IDs,
+    /// which are included when expanding `^:` task deps, hashing, and syncing.
+    pub fn create(
+        id: &str,
+        source: &str,
+        workspace_root: &Path,
+        global_config: &GlobalProjectConfig,
+        projects: &ProjectsSourceMap,
+        inferred_dependencies: &[ProjectID],
     ) -> Result<Project, ProjectError> {
         let root = workspace_root.join(&path::normalize_separators(source));
         let log_target = format!("moon:project:{}", id);
@@ -277,21 +599,51 @@ impl Project {
         }
 
         let config = load_project_config(&log_target, &root, source)?;
-        let file_groups = create_file_groups_from_config(&log_target, &config, global_config);
+
+        // Only inherit global file groups and tasks that apply to the project
+        let scoped_global_config = scope_global_config(global_config, &config);
+
+        let mut file_groups =
+            create_file_groups_from_config(&log_target, &config, &scoped_global_config);
+
+        // Resolve references to file groups in other projects
+        let context = FileGroupRefContext {
+            file_groups: RefCell::new(HashMap::from([(id.to_owned(), file_groups.clone())])),
+            global_config,
+            log_target: &log_target,
+            projects,
+            workspace_root,
+        };
+
+        for file_group in file_groups.values_mut() {
+            resolve_file_group_references(&context, file_group, id, &[])?;
+        }
+
+        let inferred_dependencies = inferred_dependencies
+            .iter()
+            .filter(|dep_id| match &config {
+                Some(cfg) => !cfg.depends_on.contains(dep_id),
+                None => true,
+            })
+            .cloned()
+            .collect::<Vec<ProjectID>>();
+
         let tasks = create_tasks_from_config(
             &log_target,
             &config,
-            global_config,
+            &scoped_global_config,
             workspace_root,
             &root,
             id,
             &file_groups,
+            &inferred_dependencies,
         )?;
 
         Ok(Project {
             config,
             file_groups,
             id: String::from(id),
+            inferred_dependencies,
             log_target,
             root,
             source: String::from(source),
@@ -299,7 +651,8 @@ impl Project {
         })
     }
 
-    /// Return a list of project IDs this project depends on.
+    /// Return a list of project IDs this project depends on, both explicitly
+    /// through `dependsOn`, and inferred by the project graph.
     pub fn get_dependencies(&self) -> Vec<ProjectID> {
         let mut depends_on = vec![];
 
@@ -307,11 +660,38 @@ impl Project {
             depends_on.extend_from_slice(&config.depends_on);
         }
 
+        depends_on.extend_from_slice(&self.inferred_dependencies);
+
         depends_on.sort();
 
         depends_on
     }
 
+    /// Return the configured project type, or the default type if not configured.
+    pub fn get_type(&self) -> ProjectType {
+        match &self.config {
+            Some(config) => config.type_of.clone(),
+            None => ProjectType::default(),
+        }
+    }
+
+    /// Return true if the project has been configured with the provided tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        match &self.config {
+            Some(config) => config.tags.iter().any(|t| t == tag),
+            None => false,
+        }
+    }
+
+    /// Return the first touched file (sorted) that resides within the project root, if any.
+    pub fn get_touched_file(&self, touched_files: &TouchedFilePaths) -> Option<PathBuf> {
+        touched_files
+            .iter()
+            .filter(|file| file.starts_with(&self.root))
+            .min()
+            .cloned()
+    }
+
     /// Return the "package.json" name, if the file exists.
     pub async fn get_package_name(&self) -> Result<Option<String>, ProjectError> {
         if let Some(json) = self.load_package_json().await? {
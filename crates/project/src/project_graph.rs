@@ -1,29 +1,105 @@
 use crate::constants::ROOT_NODE_ID;
 use crate::errors::ProjectError;
-use crate::helpers::detect_projects_with_globs;
-use crate::project::Project;
+use crate::helpers::{detect_projects_with_globs, enum_to_string, find_shortest_cycle};
+use crate::project::{load_project_config, Project};
+use crate::target::{Target, TargetProject};
 use crate::types::{ProjectsSourceMap, TouchedFilePaths};
 use moon_cache::CacheEngine;
 use moon_config::constants::{
     CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME, CONFIG_WORKSPACE_FILENAME, FLAG_PROJECTS_USING_GLOB,
 };
-use moon_config::{GlobalProjectConfig, ProjectID};
+use moon_config::package::PackageJson;
+use moon_config::tsconfig::TsConfigJson;
+use moon_config::{ConstraintsConfig, GlobalProjectConfig, ProjectID};
 use moon_logger::{color, debug, map_list, trace};
+use moon_utils::{fs, path};
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockWriteGuard};
 
-type GraphType = DiGraph<Project, ()>;
+/// How a dependency between 2 projects was declared.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencySource {
+    /// Through `dependsOn` in `project.yml`, with the entry as written
+    /// (an ID or alias).
+    Explicit(String),
+
+    /// Through another config file within the project, either `package.json`
+    /// dependencies, or `tsconfig.json` references and paths.
+    Inferred(String),
+}
+
+type GraphType = DiGraph<Project, DependencySource>;
+
 type IndicesType = HashMap<ProjectID, NodeIndex>;
 
 const LOG_TARGET: &str = "moon:project-graph";
 const READ_ERROR: &str = "Failed to acquire a read lock";
 const WRITE_ERROR: &str = "Failed to acquire a write lock";
 
+/// Format where a dependency between 2 projects was declared, for use in errors.
+pub fn format_dependency_origin(project: &Project, source: &DependencySource) -> String {
+    match source {
+        DependencySource::Explicit(entry) => format!(
+            "<symbol>dependsOn</symbol> entry <id>{}</id> in <file>{}/{}</file>",
+            entry, project.source, CONFIG_PROJECT_FILENAME
+        ),
+        DependencySource::Inferred(file) => {
+            format!("inferred from <file>{}/{}</file>", project.source, file)
+        }
+    }
+}
+
+/// Validate that a dependency between 2 projects satisfies the workspace constraints.
+fn validate_dependency_constraints(
+    constraints: &ConstraintsConfig,
+    project: &Project,
+    dep: &Project,
+    source: &DependencySource,
+) -> Result<(), ProjectError> {
+    let dep_type = dep.get_type();
+
+    if !constraints.dependency_types.is_empty() && !constraints.dependency_types.contains(&dep_type)
+    {
+        return Err(ProjectError::ConstraintDependencyType(
+            project.id.clone(),
+            dep.id.clone(),
+            format_dependency_origin(project, source),
+            enum_to_string(&dep_type),
+        ));
+    }
+
+    let mut tags = constraints
+        .forbidden_tag_dependencies
+        .keys()
+        .filter(|tag| project.has_tag(tag))
+        .collect::<Vec<&String>>();
+
+    tags.sort();
+
+    for tag in tags {
+        let forbidden_tags = constraints.forbidden_tag_dependencies.get(tag).unwrap();
+
+        if let Some(dep_tag) = forbidden_tags.iter().find(|t| dep.has_tag(t)) {
+            return Err(ProjectError::ConstraintForbiddenTag(
+                project.id.clone(),
+                dep.id.clone(),
+                format_dependency_origin(project, source),
+                tag.to_owned(),
+                dep_tag.to_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 async fn load_projects_from_cache(
     workspace_root: &Path,
     projects: &ProjectsSourceMap,
@@ -75,6 +151,14 @@ async fn load_projects_from_cache(
 }
 
 pub struct ProjectGraph {
+    /// Mapping of aliases (`alias` in `project.yml`, and `package.json` names)
+    /// to project IDs, lazily loaded when an unknown ID is resolved.
+    aliases: Arc<RwLock<Option<HashMap<String, ProjectID>>>>,
+
+    /// Constraints that dependencies between projects must satisfy.
+    /// Is the `constraints` setting in `.moon/workspace.yml`.
+    constraints: ConstraintsConfig,
+
     /// The global project configuration that all projects inherit from.
     /// Is loaded from `.moon/project.yml`.
     global_config: GlobalProjectConfig,
@@ -86,6 +170,18 @@ pub struct ProjectGraph {
     /// to query the graph by ID as it only supports it by index.
     indices: Arc<RwLock<IndicesType>>,
 
+    /// Infer dependencies from `package.json` dependencies that
+    /// reference another project's package name.
+    infer_dependencies: bool,
+
+    /// Infer dependencies from `tsconfig.json` references and paths that
+    /// resolve into another project's root, using this file name.
+    infer_tsconfig_name: Option<String>,
+
+    /// Mapping of `package.json` names to project IDs, lazily
+    /// loaded when dependencies are being inferred.
+    package_names: Arc<RwLock<Option<HashMap<String, ProjectID>>>>,
+
     /// The mapping of projects by ID to a relative file system location.
     /// Is the `projects` setting in `.moon/workspace.yml`.
     projects_config: HashMap<ProjectID, String>,
@@ -118,15 +214,41 @@ impl ProjectGraph {
         });
 
         Ok(ProjectGraph {
+            aliases: Arc::new(RwLock::new(None)),
+            constraints: ConstraintsConfig::default(),
             global_config,
             graph: Arc::new(RwLock::new(graph)),
             indices: Arc::new(RwLock::new(HashMap::new())),
+            infer_dependencies: false,
+            infer_tsconfig_name: None,
+            package_names: Arc::new(RwLock::new(None)),
             projects_config: load_projects_from_cache(workspace_root, projects_config, cache)
                 .await?,
             workspace_root: workspace_root.to_path_buf(),
         })
     }
 
+    /// Set the constraints that are validated when dependencies are loaded into
+    /// the graph. Must be called before projects are loaded.
+    pub fn set_constraints(&mut self, constraints: ConstraintsConfig) -> &mut Self {
+        self.constraints = constraints;
+        self
+    }
+
+    /// Enable inferring implicit dependencies from each project's `package.json`.
+    /// Must be called before projects are loaded.
+    pub fn set_infer_dependencies(&mut self, infer: bool) -> &mut Self {
+        self.infer_dependencies = infer;
+        self
+    }
+
+    /// Enable inferring implicit dependencies from each project's `tsconfig.json`
+    /// (of the provided file name). Must be called before projects are loaded.
+    pub fn set_infer_tsconfig_dependencies(&mut self, tsconfig_name: Option<String>) -> &mut Self {
+        self.infer_tsconfig_name = tsconfig_name;
+        self
+    }
+
     /// Return a list of all configured project IDs in ascending order.
     pub fn ids(&self) -> Vec<ProjectID> {
         let mut nodes: Vec<ProjectID> = self.projects_config.keys().cloned().collect();
@@ -139,6 +261,8 @@ impl ProjectGraph {
     /// project graph. If the project does not exist or has been
     /// misconfigured, an error will be returned.
     pub fn load(&self, id: &str) -> Result<Project, ProjectError> {
+        let id = &self.resolve_id(id)?;
+
         // Check if the project already exists in read-only mode,
         // so that it may be dropped immediately after!
         {
@@ -154,9 +278,65 @@ impl ProjectGraph {
         // Otherwise we need to load the project in write mode
         let mut indices = self.indices.write().expect(WRITE_ERROR);
         let mut graph = self.graph.write().expect(WRITE_ERROR);
-        let index = self.internal_load(id, &mut indices, &mut graph)?;
+        let node_count = graph.node_count();
+
+        let result = match self.internal_load(id, &mut indices, &mut graph) {
+            Ok(index) => self.detect_cycle(&graph).map(|_| index),
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(index) => Ok(graph.node_weight(index).unwrap().clone()),
+            Err(error) => {
+                // Remove every project inserted while loading, so that
+                // a failed load does not leave a partial graph behind
+                for index in (node_count..graph.node_count()).rev() {
+                    graph.remove_node(NodeIndex::new(index));
+                }
 
-        Ok(graph.node_weight(index).unwrap().clone())
+                indices.retain(|_, index| index.index() < node_count);
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Return the project ID for the provided ID or alias. Unknown values are
+    /// returned as-is, so that loading them fails with the original value.
+    pub fn resolve_id(&self, id_or_alias: &str) -> Result<ProjectID, ProjectError> {
+        if id_or_alias == ROOT_NODE_ID || self.projects_config.contains_key(id_or_alias) {
+            return Ok(id_or_alias.to_owned());
+        }
+
+        let mut aliases = self.aliases.write().expect(WRITE_ERROR);
+
+        if aliases.is_none() {
+            *aliases = Some(self.load_aliases()?);
+        }
+
+        Ok(match aliases.as_ref().unwrap().get(id_or_alias) {
+            Some(id) => id.to_owned(),
+            None => id_or_alias.to_owned(),
+        })
+    }
+
+    /// Return every alias collision between configured projects, so that
+    /// they can be reported at once, instead of failing on the first.
+    pub fn find_alias_collisions(&self) -> Result<Vec<ProjectError>, ProjectError> {
+        Ok(self.collect_aliases()?.1)
+    }
+
+    /// Return the target with its project alias (if used) replaced by the project ID.
+    pub fn resolve_target(&self, target: &Target) -> Result<Target, ProjectError> {
+        if let TargetProject::Id(id_or_alias) = &target.project {
+            let id = self.resolve_id(id_or_alias)?;
+
+            if &id != id_or_alias {
+                return Target::parse(&Target::format(&id, &target.task_id)?);
+            }
+        }
+
+        Ok(target.clone())
     }
 
     /// Return a list of direct project IDs that the defined project depends on.
@@ -172,6 +352,30 @@ impl ProjectGraph {
         Ok(deps)
     }
 
+    /// Return a list of direct project IDs that the defined project depends on,
+    /// paired with how each dependency was declared.
+    pub fn get_dependency_sources_of(
+        &self,
+        project: &Project,
+    ) -> Result<Vec<(ProjectID, DependencySource)>, ProjectError> {
+        let indices = self.indices.read().expect(READ_ERROR);
+        let graph = self.graph.read().expect(READ_ERROR);
+
+        let mut deps = graph
+            .edges_directed(*indices.get(&project.id).unwrap(), Direction::Outgoing)
+            .map(|edge| {
+                (
+                    graph.node_weight(edge.target()).unwrap().id.clone(),
+                    edge.weight().clone(),
+                )
+            })
+            .collect::<Vec<(ProjectID, DependencySource)>>();
+
+        deps.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(deps)
+    }
+
     /// Return a list of project IDs that require the defined project.
     pub fn get_dependents_of(&self, project: &Project) -> Result<Vec<ProjectID>, ProjectError> {
         let indices = self.indices.read().expect(READ_ERROR);
@@ -186,6 +390,51 @@ impl ProjectGraph {
         Ok(deps)
     }
 
+    /// Return the shortest chain of project IDs that connects the `from` project
+    /// to the `to` project by following dependencies, or `None` if no chain exists.
+    pub fn get_path_between(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<Vec<ProjectID>>, ProjectError> {
+        self.load(from)?;
+        self.load(to)?;
+
+        let indices = self.indices.read().expect(READ_ERROR);
+        let graph = self.graph.read().expect(READ_ERROR);
+        let start = *indices.get(from).unwrap();
+        let end = *indices.get(to).unwrap();
+
+        // Breadth-first so that the first path found is the shortest
+        let mut parents: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(index) = queue.pop_front() {
+            if index == end {
+                let mut path = vec![graph.node_weight(end).unwrap().id.clone()];
+                let mut current = end;
+
+                while let Some(parent) = parents.get(&current) {
+                    path.push(graph.node_weight(*parent).unwrap().id.clone());
+                    current = *parent;
+                }
+
+                path.reverse();
+
+                return Ok(Some(path));
+            }
+
+            for neighbor in graph.neighbors_directed(index, Direction::Outgoing) {
+                if neighbor != start && !parents.contains_key(&neighbor) {
+                    parents.insert(neighbor, index);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Return true if global config files have been touched.
     pub fn is_globally_affected(&self, touched_files: &TouchedFilePaths) -> bool {
         let cfg_dir = self.workspace_root.join(CONFIG_DIRNAME);
@@ -198,7 +447,15 @@ impl ProjectGraph {
             return true;
         }
 
-        false
+        // Scoped global project configs: `.moon/project.<scope>.yml`
+        touched_files.iter().any(|file| {
+            file.parent() == Some(cfg_dir.as_path())
+                && file
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| name.starts_with("project.") && name.ends_with(".yml"))
+                    .unwrap_or(false)
+        })
     }
 
     /// Format as a DOT string.
@@ -239,6 +496,305 @@ impl ProjectGraph {
         format!("{:?}", dot)
     }
 
+    /// Fail with the shortest cycle found in the graph, including the
+    /// config file each dependency edge was declared in.
+    fn detect_cycle(&self, graph: &GraphType) -> Result<(), ProjectError> {
+        let cycle = match find_shortest_cycle(graph) {
+            Some(cycle) => cycle,
+            None => return Ok(()),
+        };
+
+        let ids = cycle
+            .iter()
+            .map(|index| graph.node_weight(*index).unwrap().id.clone())
+            .collect::<Vec<ProjectID>>();
+
+        let origins = cycle
+            .windows(2)
+            .map(|pair| {
+                let from = graph.node_weight(pair[0]).unwrap();
+                let to = graph.node_weight(pair[1]).unwrap();
+                let source = graph
+                    .find_edge(pair[0], pair[1])
+                    .map(|e| graph[e].clone())
+                    .unwrap_or_else(|| DependencySource::Explicit(to.id.clone()));
+
+                format!(
+                    "  <id>{}</id> → <id>{}</id> ({})",
+                    from.id,
+                    to.id,
+                    format_dependency_origin(from, &source)
+                )
+            })
+            .collect::<Vec<String>>();
+
+        Err(ProjectError::DependencyCycleDetected(
+            ids.join(" → "),
+            origins.join("\n"),
+        ))
+    }
+
+    /// Return all dependencies between loaded projects that violate the provided
+    /// constraints, sorted by project ID.
+    pub fn find_constraint_violations(&self, constraints: &ConstraintsConfig) -> Vec<ProjectError> {
+        let graph = self.graph.read().expect(READ_ERROR);
+
+        let mut edges = graph
+            .edge_references()
+            .filter(|edge| edge.source() != NodeIndex::new(0))
+            .map(|edge| {
+                (
+                    graph.node_weight(edge.source()).unwrap(),
+                    graph.node_weight(edge.target()).unwrap(),
+                    edge.weight(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        edges.sort_by(|a, b| (&a.0.id, &a.1.id).cmp(&(&b.0.id, &b.1.id)));
+
+        edges
+            .into_iter()
+            .filter_map(|(project, dep, source)| {
+                validate_dependency_constraints(constraints, project, dep, source).err()
+            })
+            .collect()
+    }
+
+    /// Return project IDs whose package name is referenced in the project's
+    /// `package.json` `dependencies`, `devDependencies`, or `peerDependencies`.
+    fn infer_package_dependencies_of(
+        &self,
+        project_id: &str,
+        project_root: &Path,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        let package_path = project_root.join("package.json");
+
+        if !package_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let package: PackageJson = fs::read_json_sync(&package_path)?;
+        let mut package_names = self.package_names.write().expect(WRITE_ERROR);
+
+        if package_names.is_none() {
+            *package_names = Some(self.load_package_names()?);
+        }
+
+        let package_names = package_names.as_ref().unwrap();
+        let mut deps = vec![];
+
+        for dep_set in [
+            &package.dependencies,
+            &package.dev_dependencies,
+            &package.peer_dependencies,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for name in dep_set.keys() {
+                if let Some(dep_id) = package_names.get(name) {
+                    if dep_id != project_id && !deps.contains(dep_id) {
+                        deps.push(dep_id.to_owned());
+                    }
+                }
+            }
+        }
+
+        deps.sort();
+
+        Ok(deps)
+    }
+
+    /// Return project IDs whose root contains a path from the project's `tsconfig.json`
+    /// `references`, or a `compilerOptions.paths` target.
+    fn infer_tsconfig_dependencies_of(
+        &self,
+        project_id: &str,
+        project_root: &Path,
+        tsconfig_name: &str,
+    ) -> Result<Vec<ProjectID>, ProjectError> {
+        let tsconfig_path = project_root.join(tsconfig_name);
+
+        if !tsconfig_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let tsconfig: TsConfigJson = fs::read_json_sync(&tsconfig_path)?;
+        let mut paths = vec![];
+
+        if let Some(references) = &tsconfig.references {
+            for reference in references {
+                paths.push(project_root.join(&reference.path));
+            }
+        }
+
+        if let Some(compiler_options) = &tsconfig.compiler_options {
+            let base_dir = project_root.join(compiler_options.base_url.as_deref().unwrap_or("."));
+
+            for targets in compiler_options
+                .paths
+                .iter()
+                .flat_map(|paths| paths.values())
+            {
+                for target in targets {
+                    // Only the static portion before a wildcard can be resolved
+                    paths.push(base_dir.join(target.split('*').next().unwrap_or_default()));
+                }
+            }
+        }
+
+        let mut deps = vec![];
+
+        for dep_path in paths {
+            if let Some(dep_id) = self.find_project_containing(&path::normalize(&dep_path)) {
+                if dep_id != project_id && !deps.contains(&dep_id) {
+                    deps.push(dep_id);
+                }
+            }
+        }
+
+        deps.sort();
+
+        Ok(deps)
+    }
+
+    /// Return the ID of the project with the deepest root that contains the path.
+    fn find_project_containing(&self, file: &Path) -> Option<ProjectID> {
+        self.projects_config
+            .iter()
+            .map(|(id, source)| (id, path::normalize(&self.workspace_root.join(source))))
+            .filter(|(_, root)| file.starts_with(root))
+            .max_by_key(|(_, root)| root.components().count())
+            .map(|(id, _)| id.to_owned())
+    }
+
+    /// Map the aliases of every configured project to its ID. Will fail on the
+    /// first alias collision.
+    fn load_aliases(&self) -> Result<HashMap<String, ProjectID>, ProjectError> {
+        let (aliases, collisions) = self.collect_aliases()?;
+
+        if let Some(collision) = collisions.into_iter().next() {
+            return Err(collision);
+        }
+
+        trace!(
+            target: LOG_TARGET,
+            "Loaded {} project aliases",
+            aliases.len(),
+        );
+
+        Ok(aliases)
+    }
+
+    /// Map the aliases of every configured project to its ID, and collect collisions.
+    /// An explicit `alias` collides when it's the ID of another project, or is used by
+    /// another project. A `package.json` name that's the ID of another project, or the
+    /// name of an earlier project, is ignored, as the ID or earlier project wins.
+    fn collect_aliases(
+        &self,
+    ) -> Result<(HashMap<String, ProjectID>, Vec<ProjectError>), ProjectError> {
+        let mut aliases: HashMap<String, ProjectID> = HashMap::new();
+        let mut explicit_aliases: HashSet<String> = HashSet::new();
+        let mut collisions = vec![];
+        let mut ids = self.projects_config.keys().collect::<Vec<&ProjectID>>();
+
+        ids.sort();
+
+        for id in ids {
+            let source = self.projects_config.get(id).unwrap();
+            let root = self.workspace_root.join(path::normalize_separators(source));
+            let mut names = vec![];
+
+            if let Some(config) = load_project_config(LOG_TARGET, &root, source)? {
+                names.extend(config.alias.map(|alias| (alias, true)));
+            }
+
+            let package_path = root.join("package.json");
+
+            if package_path.exists() {
+                let package: PackageJson = fs::read_json_sync(&package_path)?;
+
+                names.extend(package.name.map(|name| (name, false)));
+            }
+
+            for (name, explicit) in names {
+                if &name == id {
+                    continue;
+                }
+
+                if self.projects_config.contains_key(&name) {
+                    if explicit {
+                        collisions.push(ProjectError::AliasCollision(
+                            name.clone(),
+                            name,
+                            id.to_owned(),
+                        ));
+                    }
+
+                    continue;
+                }
+
+                match aliases.get(&name) {
+                    Some(existing_id) if existing_id != id => {
+                        if explicit || explicit_aliases.contains(&name) {
+                            collisions.push(ProjectError::AliasCollision(
+                                name,
+                                existing_id.to_owned(),
+                                id.to_owned(),
+                            ));
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        if explicit {
+                            explicit_aliases.insert(name.clone());
+                        }
+
+                        aliases.insert(name, id.to_owned());
+                    }
+                };
+            }
+        }
+
+        Ok((aliases, collisions))
+    }
+
+    /// Map the `package.json` name of every configured project to its ID.
+    fn load_package_names(&self) -> Result<HashMap<String, ProjectID>, ProjectError> {
+        let mut package_names = HashMap::new();
+
+        for (id, source) in &self.projects_config {
+            let package_path = self.workspace_root.join(source).join("package.json");
+
+            if package_path.exists() {
+                let package: PackageJson = fs::read_json_sync(&package_path)?;
+
+                if let Some(name) = package.name {
+                    package_names.insert(name, id.to_owned());
+                }
+            }
+        }
+
+        Ok(package_names)
+    }
+
+    /// Validate a dependency against the configured constraints before it's added.
+    fn validate_edge(
+        &self,
+        graph: &GraphType,
+        node_index: NodeIndex,
+        dep_index: NodeIndex,
+        source: &DependencySource,
+    ) -> Result<(), ProjectError> {
+        validate_dependency_constraints(
+            &self.constraints,
+            graph.node_weight(node_index).unwrap(),
+            graph.node_weight(dep_index).unwrap(),
+            source,
+        )
+    }
+
     /// Internal method for lazily loading a project and its
     /// dependencies into the graph.
     fn internal_load(
@@ -270,25 +826,90 @@ impl ProjectGraph {
             None => return Err(ProjectError::UnconfiguredID(String::from(id))),
         };
 
-        let project = Project::new(id, source, &self.workspace_root, &self.global_config)?;
-        let depends_on = project.get_dependencies();
+        let root = self.workspace_root.join(path::normalize_separators(source));
+        let mut inferred: Vec<(ProjectID, DependencySource)> = vec![];
+
+        // Inferred before the project is created, so that they're
+        // included when expanding `^:` task deps
+        if self.infer_dependencies {
+            for dep_id in self.infer_package_dependencies_of(id, &root)? {
+                inferred.push((
+                    dep_id,
+                    DependencySource::Inferred(String::from("package.json")),
+                ));
+            }
+        }
+
+        if let Some(tsconfig_name) = &self.infer_tsconfig_name {
+            for dep_id in self.infer_tsconfig_dependencies_of(id, &root, tsconfig_name)? {
+                if !inferred.iter().any(|(id, _)| id == &dep_id) {
+                    inferred.push((dep_id, DependencySource::Inferred(tsconfig_name.clone())));
+                }
+            }
+        }
+
+        let project = Project::create(
+            id,
+            source,
+            &self.workspace_root,
+            &self.global_config,
+            &self.projects_config,
+            &inferred
+                .iter()
+                .map(|(dep_id, _)| dep_id.to_owned())
+                .collect::<Vec<ProjectID>>(),
+        )?;
+        // Paired with the entry as written, for reporting its origin
+        let depends_on = match &project.config {
+            Some(config) => config
+                .depends_on
+                .iter()
+                .map(|entry| Ok((self.resolve_id(entry)?, entry.to_owned())))
+                .collect::<Result<Vec<(ProjectID, String)>, ProjectError>>()?,
+            None => vec![],
+        };
+
+        inferred.retain(|(dep_id, _)| !depends_on.iter().any(|(id, _)| id == dep_id));
 
         // Insert the project into the graph
         let node_index = graph.add_node(project);
-        graph.add_edge(NodeIndex::new(0), node_index, ());
+        graph.add_edge(
+            NodeIndex::new(0),
+            node_index,
+            DependencySource::Explicit(id.to_owned()),
+        );
         indices.insert(id.to_owned(), node_index);
 
         if !depends_on.is_empty() {
             trace!(
                 target: LOG_TARGET,
                 "Adding dependencies {} to project {}",
-                map_list(&depends_on, |d| color::symbol(d)),
+                map_list(&depends_on, |(d, _)| color::symbol(d)),
                 color::id(id),
             );
 
-            for dep_id in depends_on {
+            for (dep_id, entry) in depends_on {
                 let dep_index = self.internal_load(dep_id.as_str(), indices, graph)?;
-                graph.add_edge(node_index, dep_index, ());
+                let source = DependencySource::Explicit(entry);
+
+                self.validate_edge(graph, node_index, dep_index, &source)?;
+                graph.add_edge(node_index, dep_index, source);
+            }
+        }
+
+        if !inferred.is_empty() {
+            trace!(
+                target: LOG_TARGET,
+                "Adding inferred dependencies {} to project {}",
+                map_list(&inferred, |(d, _)| color::symbol(d)),
+                color::id(id),
+            );
+
+            for (dep_id, dep_source) in inferred {
+                let dep_index = self.internal_load(dep_id.as_str(), indices, graph)?;
+
+                self.validate_edge(graph, node_index, dep_index, &dep_source)?;
+                graph.add_edge(node_index, dep_index, dep_source);
             }
         }
 
@@ -1,15 +1,19 @@
 use crate::errors::{ProjectError, TargetError};
+use crate::project::Project;
 use moon_config::{ProjectID, TargetID, TaskID};
-use moon_utils::regex::TARGET_PATTERN;
+use moon_utils::glob;
+use moon_utils::regex::TARGET_SELECTOR_PATTERN;
 use std::cmp::Ordering;
 use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
 pub enum TargetProject {
-    All,           // :task
-    Deps,          // ^:task
-    Id(ProjectID), // project:task
-    Own,           // ~:task
+    All,             // :task, *:task
+    Deps,            // ^:task
+    Id(ProjectID),   // project:task
+    Own,             // ~:task
+    Pattern(String), // web-*:task
+    Tag(String),     // #tag:task
 }
 
 impl fmt::Display for TargetProject {
@@ -19,24 +23,32 @@ impl fmt::Display for TargetProject {
             TargetProject::Deps => write!(f, "^"),
             TargetProject::Id(id) => write!(f, "{}", id),
             TargetProject::Own => write!(f, "~"),
+            TargetProject::Pattern(pattern) => write!(f, "{}", pattern),
+            TargetProject::Tag(tag) => write!(f, "#{}", tag),
         }
     }
 }
 
-// #[derive(Debug, PartialEq)]
-// pub enum TargetTask {
-//     All,        // project:
-//     Id(TaskID), // project:id
-// }
-
-// impl fmt::Display for TargetTask {
-//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-//         match self {
-//             TargetTask::All => write!(f, ""),
-//             TargetTask::Id(name) => write!(f, "{}", name),
-//         }
-//     }
-// }
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
+pub enum TargetTask {
+    All,             // project:*
+    Id(TaskID),      // project:task
+    Pattern(String), // project:test-*
+}
+
+impl fmt::Display for TargetTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetTask::All => write!(f, "*"),
+            TargetTask::Id(id) => write!(f, "{}", id),
+            TargetTask::Pattern(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+fn is_pattern(value: &str) -> bool {
+    value.contains('*') || value.contains('?')
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Target {
@@ -46,6 +58,8 @@ pub struct Target {
 
     pub project_id: Option<String>,
 
+    pub task: TargetTask,
+
     pub task_id: String,
 }
 
@@ -55,6 +69,7 @@ impl Target {
             id: Target::format(project_id, task_id)?,
             project: TargetProject::Id(project_id.to_owned()),
             project_id: Some(project_id.to_owned()),
+            task: TargetTask::Id(task_id.to_owned()),
             task_id: task_id.to_owned(),
         })
     }
@@ -64,11 +79,11 @@ impl Target {
     }
 
     pub fn parse(target_id: &str) -> Result<Target, ProjectError> {
-        if target_id == ":" {
+        if target_id == ":" || target_id == "*:*" || target_id == ":*" {
             return Err(ProjectError::Target(TargetError::TooWild));
         }
 
-        let matches = match TARGET_PATTERN.captures(target_id) {
+        let matches = match TARGET_SELECTOR_PATTERN.captures(target_id) {
             Some(result) => result,
             None => {
                 return Err(ProjectError::Target(TargetError::InvalidFormat(
@@ -81,31 +96,34 @@ impl Target {
 
         let project = match matches.name("project") {
             Some(value) => match value.as_str() {
-                "" => TargetProject::All,
+                "" | "*" => TargetProject::All,
                 "^" => TargetProject::Deps,
                 "~" => TargetProject::Own,
-                id => {
-                    project_id = Some(id.to_owned());
-                    TargetProject::Id(id.to_owned())
-                }
+                pattern if is_pattern(pattern) => TargetProject::Pattern(pattern.to_owned()),
+                id => match id.strip_prefix('#') {
+                    Some(tag) => TargetProject::Tag(tag.to_owned()),
+                    None => {
+                        project_id = Some(id.to_owned());
+                        TargetProject::Id(id.to_owned())
+                    }
+                },
             },
             None => TargetProject::All,
         };
 
         let task_id = matches.name("task").unwrap().as_str().to_owned();
 
-        // let task = match matches.name("task") {
-        //     Some(value) => match value.as_str() {
-        //         "" => TargetTask::All,
-        //         id => TargetTask::Id(id.to_owned()),
-        //     },
-        //     None => TargetTask::All,
-        // };
+        let task = match task_id.as_str() {
+            "*" => TargetTask::All,
+            pattern if is_pattern(pattern) => TargetTask::Pattern(pattern.to_owned()),
+            id => TargetTask::Id(id.to_owned()),
+        };
 
         Ok(Target {
             id: target_id.to_owned(),
             project,
             project_id,
+            task,
             task_id,
         })
     }
@@ -114,6 +132,36 @@ impl Target {
         Err(ProjectError::Target(error))
     }
 
+    /// Whether the target contains a wildcard pattern or tag in either
+    /// the project or task position, and must be expanded.
+    pub fn is_pattern(&self) -> bool {
+        matches!(
+            self.project,
+            TargetProject::Pattern(_) | TargetProject::Tag(_)
+        ) || !matches!(self.task, TargetTask::Id(_))
+    }
+
+    /// Whether the provided project matches the project position of this target.
+    /// Scopes that require context (`^` and `~`) never match.
+    pub fn matches_project(&self, project: &Project) -> Result<bool, ProjectError> {
+        Ok(match &self.project {
+            TargetProject::All => true,
+            TargetProject::Id(id) => id == &project.id,
+            TargetProject::Pattern(pattern) => glob::matches_id(pattern, &project.id)?,
+            TargetProject::Tag(tag) => project.has_tag(tag),
+            _ => false,
+        })
+    }
+
+    /// Whether the provided task ID matches the task position of this target.
+    pub fn matches_task(&self, task_id: &str) -> Result<bool, ProjectError> {
+        Ok(match &self.task {
+            TargetTask::All => true,
+            TargetTask::Id(id) => id == task_id,
+            TargetTask::Pattern(pattern) => glob::matches_id(pattern, task_id)?,
+        })
+    }
+
     pub fn ids(&self) -> Result<(ProjectID, TaskID), ProjectError> {
         let project_id = match &self.project_id {
             Some(id) => id,
@@ -123,12 +171,12 @@ impl Target {
             },
         };
 
-        // let task_id = match &self.task {
-        //     TargetTask::Id(id) => id,
-        //     _ => return Err(ProjectError::Target(TargetError::IdOnly(self.id.clone()))),
-        // };
+        let task_id = match &self.task {
+            TargetTask::Id(id) => id,
+            _ => return Err(ProjectError::Target(TargetError::IdOnly(self.id.clone()))),
+        };
 
-        Ok((project_id.clone(), self.task_id.clone()))
+        Ok((project_id.clone(), task_id.clone()))
     }
 }
 
@@ -147,6 +195,8 @@ impl Ord for Target {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use moon_config::ProjectConfig;
+    use moon_utils::string_vec;
 
     #[test]
     fn format() {
@@ -161,8 +211,22 @@ mod tests {
                 id: String::from("foo:build"),
                 project: TargetProject::Id("foo".to_owned()),
                 project_id: Some("foo".to_owned()),
+                task: TargetTask::Id("build".to_owned()),
+                task_id: "build".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_scoped_alias() {
+        assert_eq!(
+            Target::parse("@acme/ui:build").unwrap(),
+            Target {
+                id: String::from("@acme/ui:build"),
+                project: TargetProject::Id("@acme/ui".to_owned()),
+                project_id: Some("@acme/ui".to_owned()),
+                task: TargetTask::Id("build".to_owned()),
                 task_id: "build".to_owned(),
-                // task: TargetTask::Id("build".to_owned())
             }
         );
     }
@@ -175,24 +239,12 @@ mod tests {
                 id: String::from("^:build"),
                 project: TargetProject::Deps,
                 project_id: None,
+                task: TargetTask::Id("build".to_owned()),
                 task_id: "build".to_owned(),
-                // task: TargetTask::Id("build".to_owned())
             }
         );
     }
 
-    // #[test]
-    // fn parse_deps_project_all_tasks() {
-    //     assert_eq!(
-    //         Target::parse("^:").unwrap(),
-    //         Target {
-    //             id: String::from("^:"),
-    //             project: TargetProject::Deps,
-    //             task: TargetTask::All,
-    //         }
-    //     );
-    // }
-
     #[test]
     fn parse_self_project() {
         assert_eq!(
@@ -201,23 +253,25 @@ mod tests {
                 id: String::from("~:build"),
                 project: TargetProject::Own,
                 project_id: None,
+                task: TargetTask::Id("build".to_owned()),
                 task_id: "build".to_owned(),
-                // task: TargetTask::Id("build".to_owned())
             }
         );
     }
 
-    // #[test]
-    // fn parse_self_project_all_tasks() {
-    //     assert_eq!(
-    //         Target::parse("~:").unwrap(),
-    //         Target {
-    //             id: String::from("~:"),
-    //             project: TargetProject::Own,
-    //             task: TargetTask::All,
-    //         }
-    //     );
-    // }
+    #[test]
+    fn parse_matrix_variant() {
+        assert_eq!(
+            Target::parse("foo:test[node=16,os=linux]").unwrap(),
+            Target {
+                id: String::from("foo:test[node=16,os=linux]"),
+                project: TargetProject::Id("foo".to_owned()),
+                project_id: Some("foo".to_owned()),
+                task: TargetTask::Id("test[node=16,os=linux]".to_owned()),
+                task_id: "test[node=16,os=linux]".to_owned(),
+            }
+        );
+    }
 
     #[test]
     fn parse_all_projects() {
@@ -227,27 +281,140 @@ mod tests {
                 id: String::from(":build"),
                 project: TargetProject::All,
                 project_id: None,
+                task: TargetTask::Id("build".to_owned()),
+                task_id: "build".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_all_projects_wildcard() {
+        assert_eq!(
+            Target::parse("*:build").unwrap(),
+            Target {
+                id: String::from("*:build"),
+                project: TargetProject::All,
+                project_id: None,
+                task: TargetTask::Id("build".to_owned()),
+                task_id: "build".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_all_tasks() {
+        assert_eq!(
+            Target::parse("foo:*").unwrap(),
+            Target {
+                id: String::from("foo:*"),
+                project: TargetProject::Id("foo".to_owned()),
+                project_id: Some("foo".to_owned()),
+                task: TargetTask::All,
+                task_id: "*".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_project_pattern() {
+        assert_eq!(
+            Target::parse("web-*:build").unwrap(),
+            Target {
+                id: String::from("web-*:build"),
+                project: TargetProject::Pattern("web-*".to_owned()),
+                project_id: None,
+                task: TargetTask::Id("build".to_owned()),
                 task_id: "build".to_owned(),
-                // task: TargetTask::Id("build".to_owned())
             }
         );
     }
 
-    // #[test]
-    // fn parse_all_tasks() {
-    //     assert_eq!(
-    //         Target::parse("foo:").unwrap(),
-    //         Target {
-    //             id: String::from("foo:"),
-    //             project: TargetProject::Id("foo".to_owned()),
-    //             task: TargetTask::All,
-    //         }
-    //     );
-    // }
+    #[test]
+    fn parse_task_pattern() {
+        assert_eq!(
+            Target::parse("*:test-*").unwrap(),
+            Target {
+                id: String::from("*:test-*"),
+                project: TargetProject::All,
+                project_id: None,
+                task: TargetTask::Pattern("test-*".to_owned()),
+                task_id: "test-*".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_tag_project() {
+        assert_eq!(
+            Target::parse("#frontend:lint").unwrap(),
+            Target {
+                id: String::from("#frontend:lint"),
+                project: TargetProject::Tag("frontend".to_owned()),
+                project_id: None,
+                task: TargetTask::Id("lint".to_owned()),
+                task_id: "lint".to_owned(),
+            }
+        );
+    }
 
     #[test]
     #[should_panic(expected = "Target(TooWild)")]
     fn parse_too_wild() {
         Target::parse(":").unwrap();
     }
+
+    #[test]
+    #[should_panic(expected = "Target(TooWild)")]
+    fn parse_too_wild_patterns() {
+        Target::parse("*:*").unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Target(IdOnly(\"foo:test-*\"))")]
+    fn ids_errors_for_patterns() {
+        Target::parse("foo:test-*").unwrap().ids().unwrap();
+    }
+
+    fn create_project(id: &str, tags: Vec<String>) -> Project {
+        Project {
+            id: id.to_owned(),
+            config: Some(ProjectConfig {
+                tags,
+                ..ProjectConfig::default()
+            }),
+            ..Project::default()
+        }
+    }
+
+    #[test]
+    fn matches_projects_and_tasks() {
+        let target = Target::parse("web-*:test-?").unwrap();
+
+        assert!(target.is_pattern());
+        assert!(target
+            .matches_project(&create_project("web-app", vec![]))
+            .unwrap());
+        assert!(!target
+            .matches_project(&create_project("app", vec![]))
+            .unwrap());
+        assert!(target.matches_task("test-a").unwrap());
+        assert!(!target.matches_task("build").unwrap());
+        assert!(!Target::parse("foo:build").unwrap().is_pattern());
+    }
+
+    #[test]
+    fn matches_projects_by_tag() {
+        let target = Target::parse("#frontend:lint").unwrap();
+
+        assert!(target.is_pattern());
+        assert!(target
+            .matches_project(&create_project("app", string_vec!["react", "frontend"]))
+            .unwrap());
+        assert!(!target
+            .matches_project(&create_project("api", string_vec!["backend"]))
+            .unwrap());
+        assert!(!target
+            .matches_project(&create_project("lib", vec![]))
+            .unwrap());
+    }
 }
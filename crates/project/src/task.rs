@@ -1,18 +1,39 @@
 use crate::errors::{ProjectError, TargetError};
 use crate::target::{Target, TargetProject};
-use crate::token::TokenResolver;
+use crate::token::{resolve_runtime_vars, TokenResolver, TokenRuntimeData, RUNTIME_TOKEN_VARS};
 use crate::types::{EnvVars, ExpandedFiles, TouchedFilePaths};
 use moon_config::{
-    FilePath, FilePathOrGlob, TargetID, TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
+    FilePath, FilePathOrGlob, TargetID, TaskConfig, TaskDependencyConfig, TaskID,
+    TaskMergeStrategy, TaskOptionsConfig, TaskType,
 };
 use moon_logger::{color, debug, map_list, trace, Logable};
-use moon_utils::{glob, path, string_vec};
+use moon_utils::regex::{Captures, TOKEN_VAR_PATTERN};
+use moon_utils::{dotenv, glob, path, string_vec};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Globs are stored as absolute paths, but walking the file system requires
+/// patterns relative to the directory being walked.
+fn create_workspace_relative_glob(
+    output_glob: &str,
+    workspace_root: &Path,
+) -> Result<String, ProjectError> {
+    let root = glob::normalize(workspace_root)?;
+
+    Ok(match output_glob.strip_prefix(&root) {
+        Some(rel) => rel.trim_start_matches('/').to_owned(),
+        None => output_glob.to_owned(),
+    })
+}
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskOptions {
+    pub env_file: Option<Vec<FilePath>>,
+
     pub merge_args: TaskMergeStrategy,
 
     pub merge_deps: TaskMergeStrategy,
@@ -23,6 +44,8 @@ pub struct TaskOptions {
 
     pub merge_outputs: TaskMergeStrategy,
 
+    pub mutex: Vec<String>,
+
     pub retry_count: u8,
 
     pub run_in_ci: bool,
@@ -32,6 +55,10 @@ pub struct TaskOptions {
 
 impl TaskOptions {
     pub fn merge(&mut self, config: &TaskOptionsConfig) {
+        if let Some(env_file) = &config.env_file {
+            self.env_file = Some(env_file.clone());
+        }
+
         if let Some(merge_args) = &config.merge_args {
             self.merge_args = merge_args.clone();
         }
@@ -52,6 +79,10 @@ impl TaskOptions {
             self.merge_outputs = merge_outputs.clone();
         }
 
+        if let Some(mutex) = &config.mutex {
+            self.mutex = mutex.clone();
+        }
+
         if let Some(retry_count) = &config.retry_count {
             self.retry_count = *retry_count;
         }
@@ -66,6 +97,99 @@ impl TaskOptions {
     }
 }
 
+/// A dependency on another target, with optional args and env vars
+/// that are passed to the dependency when it runs.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TaskDependency {
+    /// Only enforce ordering when the target is already in the graph,
+    /// instead of inserting it.
+    pub after: bool,
+
+    pub args: Vec<String>,
+
+    pub env: EnvVars,
+
+    /// Ignore the dependency when its project or task does not exist.
+    pub optional: bool,
+
+    pub target: TargetID,
+}
+
+impl TaskDependency {
+    pub fn new(target: &str) -> Self {
+        TaskDependency {
+            target: target.to_owned(),
+            ..TaskDependency::default()
+        }
+    }
+
+    pub fn from_config(config: &TaskDependencyConfig) -> Self {
+        TaskDependency {
+            after: config.after.unwrap_or_default(),
+            args: config.args.clone().unwrap_or_default(),
+            env: config.env.clone().unwrap_or_default(),
+            optional: config.optional.unwrap_or_default(),
+            target: config.target.clone(),
+        }
+    }
+
+    pub fn has_overrides(&self) -> bool {
+        !self.args.is_empty() || !self.env.is_empty()
+    }
+
+    /// Return true if the dependency must exist and always be ran.
+    pub fn is_required(&self) -> bool {
+        !self.after && !self.optional
+    }
+
+    /// Merge another declaration of the same dependency into this one,
+    /// where the strictest kind wins: required, then optional, then after.
+    pub fn merge_kind(&mut self, other: &TaskDependency) {
+        let required = self.is_required() || other.is_required();
+
+        self.after = self.after && other.after;
+        self.optional = !required && !self.after;
+    }
+
+    /// Return a unique ID for this dependency. When args or env vars are overridden,
+    /// a short hash of them is appended to the target (`app:build@1a2b3c4d`),
+    /// so that each combination is ran and cached separately.
+    pub fn get_id(&self) -> TargetID {
+        if !self.has_overrides() {
+            return self.target.clone();
+        }
+
+        // Hash a serialized structure so that args and env vars can't collide
+        let env: BTreeMap<&String, &String> = self.env.iter().collect();
+        let overrides = serde_json::json!({
+            "args": self.args,
+            "env": env,
+        });
+        let mut sha = Sha256::new();
+
+        sha.update(overrides.to_string().as_bytes());
+
+        let hash = format!("{:x}", sha.finalize());
+
+        format!("{}@{}", self.target, &hash[0..8])
+    }
+
+    /// Apply the overridden args and env vars onto the dependency's task. Args are
+    /// appended, while env vars take precedence and are hashed as inputs.
+    pub fn apply_to(&self, task: &Task) -> Task {
+        let mut task = task.clone();
+
+        task.args.extend(self.args.clone());
+
+        for (key, value) in &self.env {
+            task.env.insert(key.clone(), value.clone());
+            task.input_vars.insert(key.clone());
+        }
+
+        task
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Task {
@@ -73,7 +197,7 @@ pub struct Task {
 
     pub command: String,
 
-    pub deps: Vec<TargetID>,
+    pub deps: Vec<TaskDependency>,
 
     pub env: EnvVars,
 
@@ -83,19 +207,32 @@ pub struct Task {
 
     pub input_paths: ExpandedFiles,
 
+    pub input_vars: HashSet<String>,
+
     #[serde(skip)]
     pub log_target: String,
 
+    /// Parameter sets to fan out over, with a variant per combination.
+    pub matrix: BTreeMap<String, Vec<String>>,
+
+    /// Values of the combination when this task is a matrix variant.
+    pub matrix_values: BTreeMap<String, String>,
+
     pub options: TaskOptions,
 
     pub outputs: Vec<FilePath>,
 
+    pub output_globs: Vec<FilePathOrGlob>,
+
     pub output_paths: ExpandedFiles,
 
     pub target: TargetID,
 
     #[serde(rename = "type")]
     pub type_of: TaskType,
+
+    /// IDs of the matrix variants that run in place of this task.
+    pub variants: Vec<TaskID>,
 }
 
 impl Logable for Task {
@@ -115,26 +252,42 @@ impl Task {
         let task = Task {
             args: cloned_config.args.unwrap_or_default(),
             command,
-            deps: cloned_config.deps.unwrap_or_default(),
+            deps: cloned_config
+                .deps
+                .unwrap_or_default()
+                .iter()
+                .map(TaskDependency::from_config)
+                .collect(),
             env: cloned_config.env.unwrap_or_default(),
             inputs: cloned_config.inputs.unwrap_or_else(|| string_vec!["**/*"]),
             input_globs: vec![],
             input_paths: HashSet::new(),
+            input_vars: HashSet::new(),
             log_target,
+            matrix: cloned_config
+                .matrix
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            matrix_values: BTreeMap::new(),
             options: TaskOptions {
+                env_file: cloned_options.env_file,
                 merge_args: cloned_options.merge_args.unwrap_or_default(),
                 merge_deps: cloned_options.merge_deps.unwrap_or_default(),
                 merge_env: cloned_options.merge_env.unwrap_or_default(),
                 merge_inputs: cloned_options.merge_inputs.unwrap_or_default(),
                 merge_outputs: cloned_options.merge_outputs.unwrap_or_default(),
+                mutex: cloned_options.mutex.unwrap_or_default(),
                 retry_count: cloned_options.retry_count.unwrap_or_default(),
                 run_in_ci: cloned_options.run_in_ci.unwrap_or(!is_long_running),
                 run_from_workspace_root: cloned_options.run_from_workspace_root.unwrap_or_default(),
             },
             outputs: cloned_config.outputs.unwrap_or_default(),
+            output_globs: vec![],
             output_paths: HashSet::new(),
             target: target.clone(),
             type_of: cloned_config.type_of,
+            variants: vec![],
         };
 
         debug!(
@@ -147,6 +300,82 @@ impl Task {
         task
     }
 
+    /// Create a concrete task for each combination of matrix values, keyed by their
+    /// task ID (`test[browser=chrome,node=16]`). Values are substituted into
+    /// env vars and outputs here, while args are substituted when expanded.
+    pub fn create_variants(&self, task_id: &str) -> Result<Vec<(TaskID, Task)>, ProjectError> {
+        // Variants run in parallel, so they must not write to the same outputs
+        for output in &self.outputs {
+            let tokens = TOKEN_VAR_PATTERN
+                .captures_iter(output)
+                .map(|caps| caps[1].to_owned())
+                .collect::<HashSet<String>>();
+
+            for name in self.matrix.keys() {
+                if !tokens.contains(&format!("matrix.{}", name)) {
+                    return Err(ProjectError::MatrixSharedOutput(
+                        self.target.clone(),
+                        output.clone(),
+                    ));
+                }
+            }
+        }
+
+        let mut combinations: Vec<BTreeMap<String, String>> = vec![BTreeMap::new()];
+
+        for (name, values) in &self.matrix {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    values.iter().map(move |value| {
+                        let mut next = combination.clone();
+                        next.insert(name.clone(), value.clone());
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        let (project_id, _) = Target::parse(&self.target)?.ids()?;
+        let mut variants = vec![];
+
+        for values in combinations {
+            let variant_id = format!(
+                "{}[{}]",
+                task_id,
+                values
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            );
+            let target = Target::format(&project_id, &variant_id)?;
+            let mut variant = self.clone();
+
+            // Match whole tokens, so that `$matrix.node` doesn't replace
+            // the start of `$matrix.nodeVersion`
+            for value in variant.env.values_mut().chain(variant.outputs.iter_mut()) {
+                *value = TOKEN_VAR_PATTERN
+                    .replace_all(value, |caps: &Captures| {
+                        match caps[1].strip_prefix("matrix.").and_then(|n| values.get(n)) {
+                            Some(matrix_value) => matrix_value.to_owned(),
+                            None => caps[0].to_owned(),
+                        }
+                    })
+                    .to_string();
+            }
+
+            variant.log_target = format!("moon:project:{}", target);
+            variant.matrix = BTreeMap::new();
+            variant.matrix_values = values;
+            variant.target = target;
+
+            variants.push((variant_id, variant));
+        }
+
+        Ok(variants)
+    }
+
     /// Create a globset of all input globs to match with.
     pub fn create_globset(&self) -> Result<glob::GlobSet, ProjectError> {
         Ok(glob::GlobSet::new(&self.input_globs)?)
@@ -197,6 +426,15 @@ impl Task {
         Ok(())
     }
 
+    /// Expand the command to resolve token variables.
+    pub fn expand_command(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
+        if token_resolver.has_token_var(&self.command) {
+            self.command = token_resolver.resolve_var(&self.command, self)?;
+        }
+
+        Ok(())
+    }
+
     /// Expand the deps list and resolve parent/self scopes.
     pub fn expand_deps(
         &mut self,
@@ -207,32 +445,39 @@ impl Task {
             return Ok(());
         }
 
-        let mut deps: Vec<String> = vec![];
+        let mut deps: Vec<TaskDependency> = vec![];
 
         // Dont use a `HashSet` as we want to preserve order
-        let mut push_dep = |dep: String| {
-            if !deps.contains(&dep) {
-                deps.push(dep);
-            }
+        let mut push_dep = |dep: &TaskDependency, target: TargetID| {
+            let dep = TaskDependency {
+                target,
+                ..dep.clone()
+            };
+            let id = dep.get_id();
+
+            match deps.iter_mut().find(|existing| existing.get_id() == id) {
+                Some(existing) => existing.merge_kind(&dep),
+                None => deps.push(dep),
+            };
         };
 
         for dep in &self.deps {
-            let target = Target::parse(dep)?;
+            let target = Target::parse(&dep.target)?;
 
             match &target.project {
                 // ^:task
                 TargetProject::Deps => {
                     for project_id in depends_on {
-                        push_dep(Target::format(project_id, &target.task_id)?);
+                        push_dep(dep, Target::format(project_id, &target.task_id)?);
                     }
                 }
                 // ~:task
                 TargetProject::Own => {
-                    push_dep(Target::format(owner_id, &target.task_id)?);
+                    push_dep(dep, Target::format(owner_id, &target.task_id)?);
                 }
                 // project:task
                 TargetProject::Id(_) => {
-                    push_dep(dep.clone());
+                    push_dep(dep, dep.target.clone());
                 }
                 _ => {
                     target.fail_with(TargetError::NoProjectAllInTaskDeps(target.id.clone()))?;
@@ -245,18 +490,122 @@ impl Task {
         Ok(())
     }
 
+    /// Expand the env files into env vars, and merge them underneath the inline
+    /// env vars using the `mergeEnv` strategy. Files are relative to the project root,
+    /// unless prefixed with "/", and are automatically included as inputs.
+    /// Tokens within env var values are then resolved, with functions
+    /// resolving to absolute paths.
+    pub fn expand_env(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
+        self.expand_env_files(
+            token_resolver.data.workspace_root,
+            token_resolver.data.project_root,
+        )?;
+
+        let mut env = EnvVars::new();
+
+        for (key, value) in &self.env {
+            let value = if token_resolver.has_token_func(value) {
+                token_resolver
+                    .resolve_func(value, Some(self))?
+                    .iter()
+                    .map(|path| String::from(path.to_string_lossy()))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            } else if token_resolver.has_token_var(value) {
+                token_resolver.resolve_var(value, self)?
+            } else {
+                value.clone()
+            };
+
+            env.insert(key.clone(), value);
+        }
+
+        self.env = env;
+
+        Ok(())
+    }
+
+    fn expand_env_files(
+        &mut self,
+        workspace_root: &Path,
+        project_root: &Path,
+    ) -> Result<(), ProjectError> {
+        let env_files = match &self.options.env_file {
+            Some(files) if !files.is_empty() => files.clone(),
+            _ => return Ok(()),
+        };
+
+        let mut file_env: EnvVars = HashMap::new();
+
+        for env_file in &env_files {
+            let env_path = path::normalize(&path::expand_root_path(
+                env_file,
+                workspace_root,
+                project_root,
+            ));
+
+            // Always track as an input so that creating the file invalidates the cache
+            self.input_paths.insert(env_path.clone());
+
+            if !env_path.exists() {
+                trace!(
+                    target: &self.log_target,
+                    "Env file {} does not exist, skipping",
+                    color::path(&env_path)
+                );
+
+                continue;
+            }
+
+            trace!(
+                target: &self.log_target,
+                "Loading env vars from {}",
+                color::path(&env_path)
+            );
+
+            // Later files override earlier files
+            file_env.extend(dotenv::load(&env_path)?);
+        }
+
+        // File env vars are the base, and inline env vars are merged on top
+        self.env = if self.env.is_empty() {
+            file_env
+        } else {
+            self.merge_env_vars(&file_env, &self.env, &self.options.merge_env)
+        };
+
+        Ok(())
+    }
+
     /// Expand the inputs list to a set of absolute file paths, while resolving tokens.
     pub fn expand_inputs(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
         if self.inputs.is_empty() {
             return Ok(());
         }
 
-        for input in &token_resolver.resolve(&self.inputs, None)? {
-            // We cant canonicalize here as these inputs may not exist!
-            if glob::is_path_glob(input) {
-                self.input_globs.push(glob::normalize(input)?);
-            } else {
-                self.input_paths.insert(path::normalize(input));
+        for var in token_resolver.resolve_env_vars(&self.inputs)? {
+            self.input_vars.insert(var);
+        }
+
+        for input in &self.inputs {
+            // Negated globs must be resolved without the leading "!"
+            if let Some(negated) = input.strip_prefix('!') {
+                for resolved in token_resolver.resolve(&[negated.to_owned()], None)? {
+                    self.input_globs
+                        .push(format!("!{}", glob::normalize(&resolved)?));
+                }
+
+                continue;
+            }
+
+            for resolved in token_resolver.resolve(&[input.to_owned()], None)? {
+                // We cant canonicalize here as these inputs may not exist!
+                // Negated globs from file groups are already prefixed with "!".
+                if glob::is_path_glob(&resolved) {
+                    self.input_globs.push(glob::normalize(&resolved)?);
+                } else {
+                    self.input_paths.insert(path::normalize(&resolved));
+                }
             }
         }
 
@@ -269,23 +618,123 @@ impl Task {
             return Ok(());
         }
 
-        for output in &token_resolver.resolve(&self.outputs, None)? {
-            if glob::is_path_glob(output) {
-                return Err(ProjectError::NoOutputGlob(
-                    output.to_owned(),
-                    self.target.clone(),
-                ));
-            } else {
-                self.output_paths.insert(path::normalize(output));
+        for output in &self.outputs {
+            // Negated globs must be resolved without the leading "!"
+            if let Some(negated) = output.strip_prefix('!') {
+                for resolved in token_resolver.resolve(&[negated.to_owned()], None)? {
+                    self.output_globs
+                        .push(format!("!{}", glob::normalize(&resolved)?));
+                }
+
+                continue;
+            }
+
+            for resolved in token_resolver.resolve(&[output.to_owned()], None)? {
+                if glob::is_path_glob(&resolved) {
+                    self.output_globs.push(glob::normalize(&resolved)?);
+                } else {
+                    self.output_paths.insert(path::normalize(&resolved));
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Expand output paths and globs into a list of absolute file paths that currently
+    /// exist on the file system. Directories are expanded to all of their files,
+    /// and files that match a negated glob are excluded.
+    pub fn expand_output_files(&self, workspace_root: &Path) -> Result<Vec<PathBuf>, ProjectError> {
+        let mut globs = vec![];
+        let mut negations = vec![];
+
+        for output_glob in &self.output_globs {
+            match output_glob.strip_prefix('!') {
+                Some(negated) => negations.push(negated.to_owned()),
+                None => globs.push(create_workspace_relative_glob(output_glob, workspace_root)?),
+            };
+        }
+
+        let negation = if negations.is_empty() {
+            None
+        } else {
+            Some(glob::GlobSet::new(&negations)?)
+        };
+
+        let mut files = vec![];
+        let mut candidates = vec![];
+
+        for output_path in &self.output_paths {
+            if output_path.is_dir() {
+                candidates.extend(glob::walk(output_path, &string_vec!["**/*"])?);
+            } else {
+                candidates.push(output_path.to_owned());
+            }
+        }
+
+        if !globs.is_empty() {
+            candidates.extend(glob::walk(workspace_root, &globs)?);
+        }
+
+        for file in candidates {
+            if !file.is_file() || files.contains(&file) {
+                continue;
+            }
+
+            if let Some(negation) = &negation {
+                if negation.matches(&file)? {
+                    continue;
+                }
+            }
+
+            files.push(file);
+        }
+
+        files.sort();
+
+        Ok(files)
+    }
+
     /// Return true if this task is affected, based on touched files.
     /// Will attempt to find any file that matches our list of inputs.
     pub fn is_affected(&self, touched_files: &TouchedFilePaths) -> Result<bool, ProjectError> {
+        Ok(self.get_affected_by(touched_files, None)?.is_some())
+    }
+
+    /// Return the first input that affects this task, or `None` if the task is not
+    /// affected. This is either an input env var (as `$VAR`) whose current value
+    /// differs from the value recorded in its last run, or a touched file. Env vars
+    /// are only compared when the last run values are provided.
+    pub fn get_affected_by(
+        &self,
+        touched_files: &TouchedFilePaths,
+        last_run_env_vars: Option<&BTreeMap<String, String>>,
+    ) -> Result<Option<String>, ProjectError> {
+        if let Some(last_run_env_vars) = last_run_env_vars {
+            for (var, value) in self.get_input_env_vars() {
+                let last_value = last_run_env_vars
+                    .get(&var)
+                    .map(|v| v.as_str())
+                    .unwrap_or_default();
+                let affected = value != last_value;
+
+                trace!(
+                    target: &self.log_target,
+                    "Is affected by env var {} = {}",
+                    color::symbol(&var),
+                    if affected {
+                        color::success("true")
+                    } else {
+                        color::failure("false")
+                    },
+                );
+
+                if affected {
+                    return Ok(Some(format!("${}", var)));
+                }
+            }
+        }
+
         trace!(
             target: &self.log_target,
             "Checking if affected using input files: {}",
@@ -322,11 +771,32 @@ impl Task {
             );
 
             if affected {
-                return Ok(true);
+                return Ok(Some(file.to_string_lossy().to_string()));
             }
         }
 
-        Ok(false)
+        Ok(None)
+    }
+
+    /// Return the current value of each input env var. Task level env vars
+    /// take precedence, as they will override the process env when ran.
+    pub fn get_input_env_vars(&self) -> BTreeMap<String, String> {
+        self.input_vars
+            .iter()
+            .map(|var| {
+                let value = match self.env.get(var) {
+                    Some(v) => v.clone(),
+                    None => env::var(var).unwrap_or_default(),
+                };
+
+                (var.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Return true if this task is a variant of a matrix task.
+    pub fn is_variant(&self) -> bool {
+        !self.matrix_values.is_empty()
     }
 
     pub fn merge(&mut self, config: &TaskConfig) {
@@ -340,11 +810,16 @@ impl Task {
         }
 
         if let Some(args) = &config.args {
-            self.args = self.merge_string_vec(&self.args, args, &self.options.merge_args);
+            self.args = self.merge_vec(&self.args, args, &self.options.merge_args);
         }
 
         if let Some(deps) = &config.deps {
-            self.deps = self.merge_string_vec(&self.deps, deps, &self.options.merge_deps);
+            let deps = deps
+                .iter()
+                .map(TaskDependency::from_config)
+                .collect::<Vec<TaskDependency>>();
+
+            self.deps = self.merge_vec(&self.deps, &deps, &self.options.merge_deps);
         }
 
         if let Some(env) = &config.env {
@@ -352,12 +827,48 @@ impl Task {
         }
 
         if let Some(inputs) = &config.inputs {
-            self.inputs = self.merge_string_vec(&self.inputs, inputs, &self.options.merge_inputs);
+            self.inputs = self.merge_vec(&self.inputs, inputs, &self.options.merge_inputs);
+        }
+
+        if let Some(matrix) = &config.matrix {
+            self.matrix = matrix.clone().into_iter().collect();
         }
 
         if let Some(outputs) = &config.outputs {
-            self.outputs =
-                self.merge_string_vec(&self.outputs, outputs, &self.options.merge_outputs);
+            self.outputs = self.merge_vec(&self.outputs, outputs, &self.options.merge_outputs);
+        }
+    }
+
+    /// Return the runtime token variables (without the leading `$`) that are
+    /// referenced within the command, args, and env var values.
+    pub fn get_runtime_vars(&self) -> BTreeSet<String> {
+        self.args
+            .iter()
+            .chain(self.env.values())
+            .chain(std::iter::once(&self.command))
+            .flat_map(|value| {
+                TOKEN_VAR_PATTERN
+                    .captures_iter(value)
+                    .map(|caps| caps[1].to_owned())
+                    .collect::<Vec<String>>()
+            })
+            .filter(|var| RUNTIME_TOKEN_VARS.contains(&var.as_str()))
+            .collect()
+    }
+
+    /// Resolve runtime token variables within the command, args, and env var values.
+    /// Must only be called after the task has been hashed.
+    pub fn expand_runtime_vars(&mut self, data: &TokenRuntimeData) {
+        self.command = resolve_runtime_vars(&self.command, data);
+
+        self.args = self
+            .args
+            .iter()
+            .map(|arg| resolve_runtime_vars(arg, data))
+            .collect();
+
+        for value in self.env.values_mut() {
+            *value = resolve_runtime_vars(value, data);
         }
     }
 
@@ -386,16 +897,11 @@ impl Task {
         }
     }
 
-    fn merge_string_vec(
-        &self,
-        base: &[String],
-        next: &[String],
-        strategy: &TaskMergeStrategy,
-    ) -> Vec<String> {
-        let mut list: Vec<String> = vec![];
+    fn merge_vec<T: Clone>(&self, base: &[T], next: &[T], strategy: &TaskMergeStrategy) -> Vec<T> {
+        let mut list: Vec<T> = vec![];
 
-        // This is easier than .extend() as we need to clone the inner string
-        let mut merge = |inner_list: &[String]| {
+        // This is easier than .extend() as we need to clone the inner item
+        let mut merge = |inner_list: &[T]| {
             for item in inner_list {
                 list.push(item.clone());
             }
@@ -423,25 +929,482 @@ impl Task {
 mod tests {
     use crate::test::create_expanded_task;
     use moon_config::TaskConfig;
-    use moon_utils::string_vec;
     use moon_utils::test::get_fixtures_dir;
-    use std::collections::HashSet;
+    use moon_utils::{glob, string_vec};
+    use std::collections::{BTreeMap, HashSet};
+
+    mod task_dependency {
+        use super::super::{Task, TaskDependency};
+        use moon_config::TaskConfig;
+        use moon_utils::string_vec;
+        use std::collections::HashMap;
+
+        #[test]
+        fn uses_target_as_id_without_overrides() {
+            assert_eq!(TaskDependency::new("app:build").get_id(), "app:build");
+        }
+
+        #[test]
+        fn creates_unique_ids_for_overrides() {
+            let args = TaskDependency {
+                args: string_vec!["--mode=prod"],
+                ..TaskDependency::new("app:build")
+            };
+            let env = TaskDependency {
+                env: HashMap::from([("NODE_ENV".to_owned(), "production".to_owned())]),
+                ..TaskDependency::new("app:build")
+            };
+
+            assert!(args.get_id().starts_with("app:build@"));
+            assert!(env.get_id().starts_with("app:build@"));
+            assert_ne!(args.get_id(), env.get_id());
+            assert_eq!(args.get_id(), args.clone().get_id());
+        }
+
+        #[test]
+        fn doesnt_collide_args_with_env_vars() {
+            let args = TaskDependency {
+                args: string_vec!["A=B"],
+                ..TaskDependency::new("app:build")
+            };
+            let env = TaskDependency {
+                env: HashMap::from([("A".to_owned(), "B".to_owned())]),
+                ..TaskDependency::new("app:build")
+            };
+
+            assert_ne!(args.get_id(), env.get_id());
+        }
+
+        #[test]
+        fn applies_overrides_to_task() {
+            let task = Task::from_config(
+                "app:build".to_owned(),
+                &TaskConfig {
+                    command: Some("webpack".to_owned()),
+                    args: Some(string_vec!["--color"]),
+                    env: Some(HashMap::from([("KEY".to_owned(), "a".to_owned())])),
+                    ..TaskConfig::default()
+                },
+            );
+
+            let dep = TaskDependency {
+                args: string_vec!["--mode=prod"],
+                env: HashMap::from([("KEY".to_owned(), "b".to_owned())]),
+                ..TaskDependency::new("app:build")
+            };
+
+            let applied = dep.apply_to(&task);
+
+            assert_eq!(applied.args, string_vec!["--color", "--mode=prod"]);
+            assert_eq!(applied.env.get("KEY").unwrap(), "b");
+            assert!(applied.input_vars.contains("KEY"));
+        }
+
+        #[test]
+        fn merges_to_the_strictest_kind() {
+            let optional = TaskDependency {
+                optional: true,
+                ..TaskDependency::new("app:build")
+            };
+            let after = TaskDependency {
+                after: true,
+                ..TaskDependency::new("app:build")
+            };
+
+            let mut dep = after.clone();
+            dep.merge_kind(&after);
+            assert!(dep.after);
+
+            let mut dep = after.clone();
+            dep.merge_kind(&optional);
+            assert!(!dep.after);
+            assert!(dep.optional);
+
+            let mut dep = optional;
+            dep.merge_kind(&TaskDependency::new("app:build"));
+            assert!(dep.is_required());
+        }
+    }
+
+    mod create_variants {
+        use super::super::Task;
+        use moon_config::TaskConfig;
+        use moon_utils::string_vec;
+        use std::collections::HashMap;
+
+        fn create_matrix_task(outputs: Vec<String>) -> Task {
+            Task::from_config(
+                "app:build".to_owned(),
+                &TaskConfig {
+                    matrix: Some(HashMap::from([
+                        ("mode".to_owned(), string_vec!["dev", "prod"]),
+                        ("target".to_owned(), string_vec!["es5"]),
+                    ])),
+                    outputs: Some(outputs),
+                    ..TaskConfig::default()
+                },
+            )
+        }
+
+        #[test]
+        fn substitutes_values_into_outputs() {
+            let task = create_matrix_task(string_vec!["dist/$matrix.mode-$matrix.target"]);
+            let variants = task.create_variants("build").unwrap();
+
+            assert_eq!(variants[0].0, "build[mode=dev,target=es5]");
+            assert_eq!(variants[0].1.outputs, string_vec!["dist/dev-es5"]);
+            assert_eq!(variants[1].0, "build[mode=prod,target=es5]");
+            assert_eq!(variants[1].1.outputs, string_vec!["dist/prod-es5"]);
+        }
+
+        #[test]
+        #[should_panic(expected = "MatrixSharedOutput(\"app:build\", \"dist/$matrix.mode\")")]
+        fn errors_if_outputs_dont_reference_every_value() {
+            let task = create_matrix_task(string_vec!["dist/$matrix.mode"]);
+
+            task.create_variants("build").unwrap();
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "MatrixSharedOutput(\"app:build\", \"dist/$matrix.modeName-$matrix.target\")"
+        )]
+        fn errors_if_outputs_only_reference_a_longer_name() {
+            let task = create_matrix_task(string_vec!["dist/$matrix.modeName-$matrix.target"]);
+
+            task.create_variants("build").unwrap();
+        }
+
+        #[test]
+        fn only_substitutes_whole_tokens() {
+            let mut task = create_matrix_task(string_vec!["dist/$matrix.mode-$matrix.target"]);
+            task.env = HashMap::from([(
+                "MODE".to_owned(),
+                "$matrix.mode,$matrix.modeName,$matrix.target".to_owned(),
+            )]);
+
+            let variants = task.create_variants("build").unwrap();
+
+            assert_eq!(
+                variants[0].1.env.get("MODE").unwrap(),
+                "dev,$matrix.modeName,es5"
+            );
+        }
+    }
+
+    mod expand_outputs {
+        use super::*;
+
+        #[test]
+        fn splits_paths_and_globs() {
+            let workspace_root = get_fixtures_dir("projects");
+            let project_root = workspace_root.join("basic");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    outputs: Some(string_vec!["build", "some/**/glob", "!some/**/*.map"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.output_paths,
+                HashSet::from([project_root.join("build")])
+            );
+            assert_eq!(
+                task.output_globs,
+                vec![
+                    glob::normalize(&project_root.join("some/**/glob")).unwrap(),
+                    format!(
+                        "!{}",
+                        glob::normalize(&project_root.join("some/**/*.map")).unwrap()
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn expands_files_and_excludes_negations() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    outputs: Some(string_vec!["dir", "*.ts", "!dir/subdir/**/*"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            let files = task.expand_output_files(&workspace_root).unwrap();
+
+            assert!(files.contains(&project_root.join("file.ts")));
+            assert!(files.contains(&project_root.join("dir/other.tsx")));
+            assert!(!files.contains(&project_root.join("dir/subdir/another.ts")));
+            assert!(!files.contains(&project_root.join("dir")));
+        }
+    }
+
+    mod expand_env {
+        use super::*;
+        use moon_config::{TaskMergeStrategy, TaskOptionsConfig};
+        use std::collections::HashMap;
+
+        fn create_env_task(
+            env_file: Vec<String>,
+            env: Option<HashMap<String, String>>,
+            merge_env: TaskMergeStrategy,
+        ) -> crate::Task {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("env-file");
+
+            create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env,
+                    options: TaskOptionsConfig {
+                        env_file: Some(env_file),
+                        merge_env: Some(merge_env),
+                        ..TaskOptionsConfig::default()
+                    },
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn loads_and_interpolates_vars() {
+            let task = create_env_task(string_vec![".env"], None, TaskMergeStrategy::Append);
+
+            assert_eq!(task.env.get("PORT").unwrap(), "3000");
+            assert_eq!(
+                task.env.get("API_URL").unwrap(),
+                "http://localhost:3000/api"
+            );
+        }
+
+        #[test]
+        fn later_files_override_earlier() {
+            let task = create_env_task(
+                string_vec![".env", ".env.local", ".env.missing"],
+                None,
+                TaskMergeStrategy::Append,
+            );
+
+            assert_eq!(task.env.get("PORT").unwrap(), "8080");
+        }
+
+        #[test]
+        fn inline_env_takes_precedence() {
+            let task = create_env_task(
+                string_vec![".env"],
+                Some(HashMap::from([("KEY".to_owned(), "inline".to_owned())])),
+                TaskMergeStrategy::Append,
+            );
+
+            assert_eq!(task.env.get("KEY").unwrap(), "inline");
+            assert_eq!(task.env.get("PORT").unwrap(), "3000");
+        }
+
+        #[test]
+        fn file_env_takes_precedence_when_prepending() {
+            let task = create_env_task(
+                string_vec![".env"],
+                Some(HashMap::from([("KEY".to_owned(), "inline".to_owned())])),
+                TaskMergeStrategy::Prepend,
+            );
+
+            assert_eq!(task.env.get("KEY").unwrap(), "file");
+        }
+
+        #[test]
+        fn adds_files_as_inputs() {
+            let workspace_root = get_fixtures_dir("base");
+            let task = create_env_task(
+                string_vec![".env", "/.env.shared"],
+                None,
+                TaskMergeStrategy::Append,
+            );
+
+            assert!(task
+                .input_paths
+                .contains(&workspace_root.join("env-file/.env")));
+            assert!(task
+                .input_paths
+                .contains(&workspace_root.join(".env.shared")));
+        }
+
+        #[test]
+        fn resolves_token_vars() {
+            let task = create_env_task(
+                string_vec![],
+                Some(HashMap::from([
+                    ("TARGET".to_owned(), "$target".to_owned()),
+                    ("BUILT_AT".to_owned(), "$timestamp".to_owned()),
+                ])),
+                TaskMergeStrategy::Append,
+            );
+
+            assert_eq!(task.env.get("TARGET").unwrap(), "project:task");
+            assert_eq!(task.env.get("BUILT_AT").unwrap(), "$timestamp");
+        }
+    }
+
+    mod expand_env_tokens {
+        use super::*;
+        use std::collections::HashMap;
+
+        #[test]
+        fn resolves_funcs_to_absolute_paths() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([
+                        ("INPUT".to_owned(), "@in(0)".to_owned()),
+                        ("OUTPUT".to_owned(), "@out(0)".to_owned()),
+                    ])),
+                    inputs: Some(string_vec!["file.ts"]),
+                    outputs: Some(string_vec!["build"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.env.get("INPUT").unwrap(),
+                &project_root.join("file.ts").to_string_lossy().to_string()
+            );
+            assert_eq!(
+                task.env.get("OUTPUT").unwrap(),
+                &project_root.join("build").to_string_lossy().to_string()
+            );
+        }
+
+        #[test]
+        fn resolves_multiple_vars_in_a_value() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    env: Some(HashMap::from([(
+                        "BIN".to_owned(),
+                        "$PATH:$workspaceRoot/bin:$projectRoot/bin".to_owned(),
+                    )])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.env.get("BIN").unwrap(),
+                &format!(
+                    "$PATH:{}/bin:{}/bin",
+                    workspace_root.to_string_lossy(),
+                    project_root.to_string_lossy()
+                )
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@files\", \"env\")")]
+        fn errors_for_unsupported_funcs() {
+            let workspace_root = get_fixtures_dir("base");
+
+            create_expanded_task(
+                &workspace_root,
+                &workspace_root.join("files-and-dirs"),
+                Some(TaskConfig {
+                    env: Some(HashMap::from([(
+                        "FILES".to_owned(),
+                        "@files(static)".to_owned(),
+                    )])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+        }
+    }
+
+    mod expand_command {
+        use super::*;
+
+        #[test]
+        fn resolves_vars() {
+            let workspace_root = get_fixtures_dir("base");
+            let task = create_expanded_task(
+                &workspace_root,
+                &workspace_root.join("files-and-dirs"),
+                Some(TaskConfig {
+                    command: Some("$workspaceRoot/scripts/build.sh".to_owned()),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                task.command,
+                format!("{}/scripts/build.sh", workspace_root.to_string_lossy())
+            );
+        }
+    }
+
+    mod runtime_vars {
+        use super::*;
+        use crate::token::TokenRuntimeData;
+        use chrono::{Local, TimeZone};
+        use std::collections::HashMap;
 
-    #[test]
-    #[should_panic(expected = "NoOutputGlob")]
-    fn errors_for_output_glob() {
-        let workspace_root = get_fixtures_dir("projects");
-        let project_root = workspace_root.join("basic");
+        fn create_runtime_task() -> crate::Task {
+            let workspace_root = get_fixtures_dir("base");
 
-        create_expanded_task(
-            &workspace_root,
-            &project_root,
-            Some(TaskConfig {
-                outputs: Some(string_vec!["some/**/glob"]),
-                ..TaskConfig::default()
-            }),
-        )
-        .unwrap();
+            create_expanded_task(
+                &workspace_root,
+                &workspace_root.join("files-and-dirs"),
+                Some(TaskConfig {
+                    args: Some(string_vec!["--date=$date", "--rev", "$hash", "$unknown"]),
+                    env: Some(HashMap::from([("BRANCH".to_owned(), "$branch".to_owned())])),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn returns_referenced_vars() {
+            let task = create_runtime_task();
+
+            assert_eq!(
+                task.get_runtime_vars().into_iter().collect::<Vec<String>>(),
+                string_vec!["branch", "date", "hash"]
+            );
+        }
+
+        #[test]
+        fn expands_args_and_env() {
+            let mut task = create_runtime_task();
+
+            task.expand_runtime_vars(&TokenRuntimeData {
+                branch: "master".to_owned(),
+                hash: "abc123".to_owned(),
+                now: Local.ymd(2022, 6, 1).and_hms(12, 30, 0),
+            });
+
+            assert_eq!(
+                task.args,
+                string_vec!["--date=2022-06-01", "--rev", "abc123", "$unknown"]
+            );
+            assert_eq!(task.env.get("BRANCH").unwrap(), "master");
+        }
     }
 
     mod is_affected {
@@ -467,6 +1430,30 @@ mod tests {
             assert!(task.is_affected(&set).unwrap());
         }
 
+        #[test]
+        fn returns_the_affecting_file() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    inputs: Some(string_vec!["file.ts"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            let mut set = HashSet::new();
+            set.insert(project_root.join("file.ts"));
+
+            assert_eq!(
+                task.get_affected_by(&set, None).unwrap(),
+                Some(project_root.join("file.ts").to_string_lossy().to_string())
+            );
+            assert_eq!(task.get_affected_by(&HashSet::new(), None).unwrap(), None);
+        }
+
         #[test]
         fn returns_true_if_matches_glob() {
             let workspace_root = get_fixtures_dir("base");
@@ -527,6 +1514,68 @@ mod tests {
             assert!(!task.is_affected(&set).unwrap());
         }
 
+        #[test]
+        fn returns_false_if_only_env_var_inputs() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    inputs: Some(string_vec!["$PATH"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert!(!task.is_affected(&HashSet::new()).unwrap());
+        }
+
+        #[test]
+        fn returns_the_affecting_env_var_if_changed_since_last_run() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    inputs: Some(string_vec!["$MOON_TEST_AFFECTED_ENV_VAR"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            std::env::set_var("MOON_TEST_AFFECTED_ENV_VAR", "foo");
+
+            let same =
+                BTreeMap::from([("MOON_TEST_AFFECTED_ENV_VAR".to_owned(), "foo".to_owned())]);
+            let changed =
+                BTreeMap::from([("MOON_TEST_AFFECTED_ENV_VAR".to_owned(), "bar".to_owned())]);
+
+            assert_eq!(
+                task.get_affected_by(&HashSet::new(), Some(&same)).unwrap(),
+                None
+            );
+            assert_eq!(
+                task.get_affected_by(&HashSet::new(), Some(&changed))
+                    .unwrap(),
+                Some("$MOON_TEST_AFFECTED_ENV_VAR".to_owned())
+            );
+            assert_eq!(
+                task.get_affected_by(&HashSet::new(), Some(&BTreeMap::new()))
+                    .unwrap(),
+                Some("$MOON_TEST_AFFECTED_ENV_VAR".to_owned())
+            );
+
+            std::env::remove_var("MOON_TEST_AFFECTED_ENV_VAR");
+
+            assert_eq!(
+                task.get_affected_by(&HashSet::new(), Some(&BTreeMap::new()))
+                    .unwrap(),
+                None
+            );
+        }
+
         #[test]
         fn returns_false_if_no_match() {
             let workspace_root = get_fixtures_dir("base");
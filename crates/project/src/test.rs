@@ -83,11 +83,13 @@ pub fn create_expanded_task(
         &config.unwrap_or_default(),
     );
     let file_groups = create_file_groups();
-    let metadata = TokenSharedData::new(&file_groups, workspace_root, project_root);
+    let metadata = TokenSharedData::new(&file_groups, workspace_root, project_root, &None);
 
     task.expand_inputs(TokenResolver::for_inputs(&metadata))?;
     task.expand_outputs(TokenResolver::for_outputs(&metadata))?;
+    task.expand_env(TokenResolver::for_env(&metadata))?; // Must be after inputs/outputs
     task.expand_args(TokenResolver::for_args(&metadata))?; // Must be last
+    task.expand_command(TokenResolver::for_args(&metadata))?;
 
     Ok(task)
 }
@@ -1,20 +1,33 @@
 use crate::errors::{ProjectError, TokenError};
 use crate::file_group::FileGroup;
+use crate::helpers::enum_to_string;
 use crate::target::Target;
 use crate::task::Task;
+use chrono::{DateTime, Local};
+use moon_config::ProjectConfig;
 use moon_logger::{color, warn};
 use moon_utils::glob;
 use moon_utils::path::expand_root_path;
+use moon_utils::regex::Captures;
 use moon_utils::regex::{
-    matches_token_func, matches_token_var, TOKEN_FUNC_ANYWHERE_PATTERN, TOKEN_FUNC_PATTERN,
-    TOKEN_VAR_PATTERN,
+    matches_env_var, matches_token_func, matches_token_var, TOKEN_FUNC_ANYWHERE_PATTERN,
+    TOKEN_FUNC_PATTERN, TOKEN_VAR_PATTERN,
 };
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+/// Token variables that can only be resolved when a task is ran, and as such,
+/// are left as-is when tasks are expanded. Resolved with `resolve_runtime_vars`.
+pub const RUNTIME_TOKEN_VARS: [&str; 6] =
+    ["branch", "date", "datetime", "hash", "time", "timestamp"];
+
+/// Runtime token variables that change on every run, and must never be hashed.
+pub const VOLATILE_TOKEN_VARS: [&str; 4] = ["date", "datetime", "time", "timestamp"];
+
 #[derive(Debug, PartialEq)]
 pub enum ResolverType {
     Args,
+    Env,
     Inputs,
     Outputs,
 }
@@ -23,6 +36,7 @@ impl ResolverType {
     pub fn context_label(&self) -> String {
         String::from(match self {
             ResolverType::Args => "args",
+            ResolverType::Env => "env",
             ResolverType::Inputs => "inputs",
             ResolverType::Outputs => "outputs",
         })
@@ -33,6 +47,9 @@ impl ResolverType {
 pub enum TokenType {
     Var(String),
 
+    // Environment variables: name
+    EnvVar(String),
+
     // File groups: token, group name
     Dirs(String, String),
     Files(String, String),
@@ -50,23 +67,32 @@ impl TokenType {
             TokenType::Dirs(_, _) => {
                 matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
             }
+            TokenType::EnvVar(_) => {
+                matches!(context, ResolverType::Inputs)
+            }
             TokenType::Files(_, _) => {
-                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
+                matches!(context, ResolverType::Args)
+                    || matches!(context, ResolverType::Inputs)
+                    || matches!(context, ResolverType::Outputs)
             }
             TokenType::Globs(_, _) => {
-                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
+                matches!(context, ResolverType::Args)
+                    || matches!(context, ResolverType::Inputs)
+                    || matches!(context, ResolverType::Outputs)
             }
             TokenType::In(_, _) => {
-                matches!(context, ResolverType::Args)
+                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Env)
             }
             TokenType::Out(_, _) => {
-                matches!(context, ResolverType::Args)
+                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Env)
             }
             TokenType::Root(_, _) => {
-                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Inputs)
+                matches!(context, ResolverType::Args)
+                    || matches!(context, ResolverType::Env)
+                    || matches!(context, ResolverType::Inputs)
             }
             TokenType::Var(_) => {
-                matches!(context, ResolverType::Args)
+                matches!(context, ResolverType::Args) || matches!(context, ResolverType::Env)
             }
         };
 
@@ -83,6 +109,7 @@ impl TokenType {
     pub fn token_label(&self) -> String {
         String::from(match self {
             TokenType::Dirs(_, _) => "@dirs",
+            TokenType::EnvVar(_) => "$VAR",
             TokenType::Files(_, _) => "@files",
             TokenType::Globs(_, _) => "@globs",
             TokenType::In(_, _) => "@in",
@@ -96,6 +123,8 @@ impl TokenType {
 pub struct TokenSharedData<'a> {
     pub file_groups: &'a HashMap<String, FileGroup>,
 
+    pub project_config: &'a Option<ProjectConfig>,
+
     pub project_root: &'a Path,
 
     pub workspace_root: &'a Path,
@@ -106,9 +135,11 @@ impl<'a> TokenSharedData<'a> {
         file_groups: &'a HashMap<String, FileGroup>,
         workspace_root: &'a Path,
         project_root: &'a Path,
+        project_config: &'a Option<ProjectConfig>,
     ) -> TokenSharedData<'a> {
         TokenSharedData {
             file_groups,
+            project_config,
             project_root,
             workspace_root,
         }
@@ -129,6 +160,13 @@ impl<'a> TokenResolver<'a> {
         }
     }
 
+    pub fn for_env(data: &'a TokenSharedData<'a>) -> TokenResolver<'a> {
+        TokenResolver {
+            context: ResolverType::Env,
+            data,
+        }
+    }
+
     pub fn for_inputs(data: &'a TokenSharedData<'a>) -> TokenResolver<'a> {
         TokenResolver {
             context: ResolverType::Inputs,
@@ -159,11 +197,16 @@ impl<'a> TokenResolver<'a> {
         false
     }
 
+    pub fn has_env_var(&self, value: &str) -> bool {
+        value.starts_with('$') && matches_env_var(value)
+    }
+
     pub fn has_token_var(&self, value: &str) -> bool {
         value.contains('$') && matches_token_var(value)
     }
 
     /// Cycle through the values, resolve any tokens, and return a list of absolute file paths.
+    /// Environment variables are skipped, and should be resolved with `resolve_env_vars`.
     /// This should only be used for `inputs` and `outputs`.
     pub fn resolve(
         &self,
@@ -173,7 +216,9 @@ impl<'a> TokenResolver<'a> {
         let mut results: Vec<PathBuf> = vec![];
 
         for value in values {
-            if self.has_token_func(value) {
+            if self.has_env_var(value) {
+                TokenType::EnvVar(value.to_owned()).check_context(&self.context)?;
+            } else if self.has_token_func(value) {
                 for resolved_value in self.resolve_func(value, task)? {
                     results.push(resolved_value);
                 }
@@ -192,6 +237,22 @@ impl<'a> TokenResolver<'a> {
         Ok(results)
     }
 
+    /// Cycle through the values and return a list of environment variable names
+    /// (without the leading `$`). This should only be used for `inputs`.
+    pub fn resolve_env_vars(&self, values: &[String]) -> Result<Vec<String>, ProjectError> {
+        let mut results: Vec<String> = vec![];
+
+        for value in values {
+            if self.has_env_var(value) {
+                TokenType::EnvVar(value.to_owned()).check_context(&self.context)?;
+
+                results.push(value.strip_prefix('$').unwrap().to_owned());
+            }
+        }
+
+        Ok(results)
+    }
+
     pub fn resolve_func(
         &self,
         value: &str,
@@ -242,25 +303,58 @@ impl<'a> TokenResolver<'a> {
         }
     }
 
+    /// Resolve every token variable within the value. Variables that are not
+    /// supported, or are resolved at runtime, are left as-is.
     pub fn resolve_var(&self, value: &str, task: &Task) -> Result<String, ProjectError> {
-        let matches = TOKEN_VAR_PATTERN.captures(value).unwrap();
-        let token = matches.get(0).unwrap().as_str(); // $var
-        let var = matches.get(1).unwrap().as_str(); // var
+        let (project_id, task_id) = Target::parse(&task.target)?.ids()?;
+        let mut error = None;
 
-        // trace!(
-        //     target: "moon:token",
-        //     "Resolving token variable {} for {} value {}",
-        //     color::id(token),
-        //     self.context.context_label(),
-        //     color::file(value)
-        // );
+        let result = TOKEN_VAR_PATTERN.replace_all(value, |caps: &Captures| {
+            let token = caps.get(0).unwrap().as_str(); // $var
+            let var = caps.get(1).unwrap().as_str(); // var
 
-        let (project_id, task_id) = Target::parse(&task.target)?.ids()?;
+            if error.is_some() {
+                return token.to_owned();
+            }
+
+            if let Err(e) = TokenType::Var(token.to_owned()).check_context(&self.context) {
+                error = Some(e);
+
+                return token.to_owned();
+            }
+
+            self.get_var_value(var, &project_id, &task_id, task)
+                .unwrap_or_else(|| token.to_owned())
+        });
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(result.into_owned()),
+        }
+    }
+
+    fn get_var_value(
+        &self,
+        var: &str,
+        project_id: &str,
+        task_id: &str,
+        task: &Task,
+    ) -> Option<String> {
         let workspace_root = self.data.workspace_root;
         let project_root = self.data.project_root;
+        let project_config = self.data.project_config.as_ref();
 
         let var_value = match var {
-            "project" => project_id,
+            "language" => enum_to_string(
+                &project_config
+                    .map(|config| config.language.clone())
+                    .unwrap_or_default(),
+            ),
+            "project" => project_id.to_owned(),
+            "projectName" => project_config
+                .and_then(|config| config.project.as_ref())
+                .map(|meta| meta.name.clone())
+                .unwrap_or_default(),
             "projectRoot" => String::from(project_root.to_string_lossy()),
             "projectSource" => String::from(
                 project_root
@@ -268,21 +362,25 @@ impl<'a> TokenResolver<'a> {
                     .unwrap()
                     .to_string_lossy(),
             ),
+            "projectType" => enum_to_string(
+                &project_config
+                    .map(|config| config.type_of.clone())
+                    .unwrap_or_default(),
+            ),
             "target" => task.target.clone(),
-            "task" => task_id,
+            "task" => task_id.to_owned(),
             "workspaceRoot" => String::from(workspace_root.to_string_lossy()),
-            _ => {
-                warn!(
-                    target: "moon:project:token",
-                    "Found a token variable in \"{}\" that is not supported, but this may be intentional, so leaving it.",
-                    value
-                );
-
-                return Ok(String::from(value));
-            }
+            // Only variants have values, and the base task is never ran
+            _ if var.starts_with("matrix.") => task
+                .matrix_values
+                .get(var.trim_start_matches("matrix."))?
+                .to_owned(),
+            // Runtime variables are resolved when the task is ran, and all others
+            // are not moon variables (`$HOME`, `$PATH`, etc), so leave them for the shell
+            _ => return None,
         };
 
-        Ok(String::from(value).replace(token, &var_value))
+        Some(var_value)
     }
 
     fn convert_string_to_u8(&self, token: &str, value: String) -> Result<u8, ProjectError> {
@@ -359,19 +457,20 @@ impl<'a> TokenResolver<'a> {
                 }
             };
 
+            let workspace_root = self.data.workspace_root;
+            let project_root = self.data.project_root;
+
             if glob::is_glob(input) {
-                match task.input_globs.iter().find(|g| g.ends_with(input)) {
-                    Some(g) => {
-                        results.push(PathBuf::from(g));
-                    }
-                    None => {
-                        return Err(error);
-                    }
-                };
-            } else {
-                let workspace_root = self.data.workspace_root;
-                let project_root = self.data.project_root;
+                // Match the exact expanded glob, as multiple globs may share a suffix
+                let expanded_glob =
+                    glob::normalize(&expand_root_path(input, workspace_root, project_root))?;
 
+                if task.input_globs.contains(&expanded_glob) {
+                    results.push(PathBuf::from(expanded_glob));
+                } else {
+                    return Err(error);
+                }
+            } else {
                 match task
                     .input_paths
                     .get(&expand_root_path(input, workspace_root, project_root))
@@ -411,28 +510,72 @@ impl<'a> TokenResolver<'a> {
             let workspace_root = self.data.workspace_root;
             let project_root = self.data.project_root;
 
-            match task
-                .output_paths
-                .get(&expand_root_path(output, workspace_root, project_root))
-            {
-                Some(p) => {
-                    results.push(p.clone());
-                }
-                None => {
+            if glob::is_glob(output) {
+                // Match the exact expanded glob, as multiple globs may share a suffix
+                let expanded_glob =
+                    glob::normalize(&expand_root_path(output, workspace_root, project_root))?;
+
+                if task.output_globs.contains(&expanded_glob) {
+                    results.push(PathBuf::from(expanded_glob));
+                } else {
                     return Err(error);
                 }
-            };
+            } else {
+                match task
+                    .output_paths
+                    .get(&expand_root_path(output, workspace_root, project_root))
+                {
+                    Some(p) => {
+                        results.push(p.clone());
+                    }
+                    None => {
+                        return Err(error);
+                    }
+                };
+            }
         }
 
         Ok(results)
     }
 }
 
+/// Values for token variables that are only known when a task is ran.
+pub struct TokenRuntimeData {
+    pub branch: String,
+
+    pub hash: String,
+
+    pub now: DateTime<Local>,
+}
+
+impl TokenRuntimeData {
+    pub fn get_var(&self, var: &str) -> Option<String> {
+        Some(match var {
+            "branch" => self.branch.clone(),
+            "date" => self.now.format("%Y-%m-%d").to_string(),
+            "datetime" => self.now.format("%Y-%m-%d_%H:%M:%S").to_string(),
+            "hash" => self.hash.clone(),
+            "time" => self.now.format("%H:%M:%S").to_string(),
+            "timestamp" => self.now.timestamp().to_string(),
+            _ => return None,
+        })
+    }
+}
+
+/// Resolve all runtime token variables within a value, while leaving others as-is.
+pub fn resolve_runtime_vars(value: &str, data: &TokenRuntimeData) -> String {
+    TOKEN_VAR_PATTERN
+        .replace_all(value, |caps: &Captures| {
+            data.get_var(&caps[1]).unwrap_or_else(|| caps[0].to_owned())
+        })
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test::{create_expanded_task, create_file_groups};
-    use moon_config::TaskConfig;
+    use moon_config::{ProjectLanguage, ProjectMetadataConfig, ProjectType, TaskConfig};
     use moon_utils::string_vec;
     use moon_utils::test::{get_fixtures_dir, wrap_glob};
     use std::path::PathBuf;
@@ -451,7 +594,7 @@ mod tests {
         let project_root = get_project_root();
         let workspace_root = get_workspace_root();
         let file_groups = create_file_groups();
-        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
         let resolver = TokenResolver::for_args(&metadata);
 
         resolver
@@ -465,7 +608,7 @@ mod tests {
         let project_root = get_project_root();
         let workspace_root = get_workspace_root();
         let file_groups = create_file_groups();
-        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
         let resolver = TokenResolver::for_args(&metadata);
 
         resolver
@@ -478,7 +621,7 @@ mod tests {
         let project_root = get_project_root();
         let workspace_root = get_workspace_root();
         let file_groups = create_file_groups();
-        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+        let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
         let resolver = TokenResolver::for_args(&metadata);
 
         assert_eq!(
@@ -498,7 +641,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -522,7 +666,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -550,7 +695,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -574,7 +720,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -601,7 +748,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             assert_eq!(
@@ -617,7 +765,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             assert_eq!(
@@ -633,7 +782,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let mut files = resolver
@@ -656,7 +806,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let mut files = resolver
@@ -679,7 +830,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             assert_eq!(
@@ -698,7 +850,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -724,7 +877,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -745,12 +899,67 @@ mod tests {
             );
         }
 
+        #[test]
+        fn supports_in_globs_with_shared_suffix() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    inputs: Some(string_vec!["src/**/*", "**/*"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve(&string_vec!["@in(1)"], Some(&task))
+                    .unwrap(),
+                vec![wrap_glob(&project_root.join("**/*"))],
+            );
+        }
+
+        #[test]
+        fn supports_out_globs_with_shared_suffix() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(
+                &workspace_root,
+                &project_root,
+                Some(TaskConfig {
+                    outputs: Some(string_vec!["dist/*.js", "*.js"]),
+                    ..TaskConfig::default()
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve(&string_vec!["@out(1)"], Some(&task))
+                    .unwrap(),
+                vec![wrap_glob(&project_root.join("*.js"))],
+            );
+        }
+
         #[test]
         fn supports_out_paths() {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(
@@ -776,7 +985,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             assert_eq!(
@@ -792,7 +1002,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_args(&metadata);
 
             let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
@@ -802,6 +1013,206 @@ mod tests {
                 "project:task"
             );
         }
+
+        #[test]
+        fn supports_project_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let project_config = Some(ProjectConfig {
+                language: ProjectLanguage::JavaScript,
+                project: Some(ProjectMetadataConfig {
+                    name: "Example".to_owned(),
+                    description: String::new(),
+                    owner: String::new(),
+                    maintainers: vec![],
+                    channel: String::new(),
+                }),
+                type_of: ProjectType::Tool,
+                ..ProjectConfig::default()
+            });
+            let metadata = TokenSharedData::new(
+                &file_groups,
+                &workspace_root,
+                &project_root,
+                &project_config,
+            );
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver.resolve_var("--lang=$language", &task).unwrap(),
+                "--lang=javascript"
+            );
+            assert_eq!(
+                resolver.resolve_var("$projectName", &task).unwrap(),
+                "Example"
+            );
+            assert_eq!(resolver.resolve_var("$projectType", &task).unwrap(), "tool");
+        }
+
+        #[test]
+        fn leaves_runtime_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            for var in RUNTIME_TOKEN_VARS {
+                let value = format!("${}", var);
+
+                assert_eq!(resolver.resolve_var(&value, &task).unwrap(), value);
+            }
+        }
+
+        #[test]
+        fn leaves_non_moon_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(resolver.resolve_var("$HOME", &task).unwrap(), "$HOME");
+            assert_eq!(resolver.resolve_var("$PATH", &task).unwrap(), "$PATH");
+        }
+
+        #[test]
+        fn supports_matrix_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_args(&metadata);
+
+            let mut task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver.resolve_var("--node=$matrix.node", &task).unwrap(),
+                "--node=$matrix.node"
+            );
+
+            task.matrix_values
+                .insert("node".to_owned(), "16".to_owned());
+
+            assert_eq!(
+                resolver.resolve_var("--node=$matrix.node", &task).unwrap(),
+                "--node=16"
+            );
+        }
+    }
+
+    mod env {
+        use super::*;
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@dirs\", \"env\")")]
+        fn doesnt_support_dirs() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            resolver.resolve_func("@dirs(static)", None).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@files\", \"env\")")]
+        fn doesnt_support_files() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            resolver.resolve_func("@files(static)", None).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"@globs\", \"env\")")]
+        fn doesnt_support_globs() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            resolver.resolve_func("@globs(static)", None).unwrap();
+        }
+
+        #[test]
+        fn supports_root() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            assert_eq!(
+                resolver.resolve_func("@root(static)", None).unwrap(),
+                vec![project_root.join("dir")],
+            );
+        }
+
+        #[test]
+        fn supports_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver
+                    .resolve_var("$projectRoot/tsconfig.build.json", &task)
+                    .unwrap(),
+                format!("{}/tsconfig.build.json", project_root.to_string_lossy())
+            );
+        }
+
+        #[test]
+        fn supports_multiple_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_env(&metadata);
+
+            let task = create_expanded_task(&workspace_root, &project_root, None).unwrap();
+
+            assert_eq!(
+                resolver.resolve_var("$projectRoot/$task", &task).unwrap(),
+                format!("{}/task", project_root.to_string_lossy())
+            );
+            assert_eq!(
+                resolver.resolve_var("$date-$project", &task).unwrap(),
+                "$date-project"
+            );
+            assert_eq!(
+                resolver
+                    .resolve_var("$PATH:$workspaceRoot/bin", &task)
+                    .unwrap(),
+                format!("$PATH:{}/bin", workspace_root.to_string_lossy())
+            );
+        }
     }
 
     mod inputs {
@@ -812,7 +1223,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             assert_eq!(
@@ -828,7 +1240,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             assert_eq!(
@@ -844,7 +1257,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             let mut files = resolver
@@ -867,7 +1281,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             let mut files = resolver
@@ -890,7 +1305,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             assert_eq!(
@@ -910,7 +1326,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             resolver.resolve(&string_vec!["@in(0)"], None).unwrap();
@@ -922,7 +1339,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             resolver.resolve(&string_vec!["@out(0)"], None).unwrap();
@@ -933,7 +1351,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             assert_eq!(
@@ -950,11 +1369,32 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_inputs(&metadata);
 
             resolver.resolve(&string_vec!["$project"], None).unwrap();
         }
+
+        #[test]
+        fn supports_env_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_inputs(&metadata);
+            let inputs = string_vec!["$NODE_ENV", "file.ts", "$API_URL"];
+
+            assert_eq!(
+                resolver.resolve(&inputs, None).unwrap(),
+                vec![project_root.join("file.ts")],
+            );
+            assert_eq!(
+                resolver.resolve_env_vars(&inputs).unwrap(),
+                string_vec!["NODE_ENV", "API_URL"],
+            );
+        }
     }
 
     mod outputs {
@@ -966,7 +1406,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
             resolver
@@ -975,31 +1416,47 @@ mod tests {
         }
 
         #[test]
-        #[should_panic(expected = "InvalidTokenContext(\"@files\", \"outputs\")")]
-        fn doesnt_support_files() {
+        fn supports_files() {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
-            resolver
+            let mut files = resolver
                 .resolve(&string_vec!["@files(static)"], None)
                 .unwrap();
+            files.sort();
+
+            assert_eq!(
+                files,
+                vec![
+                    project_root.join("dir/other.tsx"),
+                    project_root.join("dir/subdir/another.ts"),
+                    project_root.join("file.ts"),
+                ]
+            );
         }
 
         #[test]
-        #[should_panic(expected = "InvalidTokenContext(\"@globs\", \"outputs\")")]
-        fn doesnt_support_globs() {
+        fn supports_globs() {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
-            resolver
-                .resolve(&string_vec!["@globs(globs)"], None)
-                .unwrap();
+            assert_eq!(
+                resolver
+                    .resolve(&string_vec!["@globs(globs)"], None)
+                    .unwrap(),
+                vec![
+                    project_root.join("**/*.{ts,tsx}"),
+                    project_root.join("*.js")
+                ],
+            );
         }
 
         #[test]
@@ -1008,7 +1465,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
             resolver.resolve(&string_vec!["@in(0)"], None).unwrap();
@@ -1020,7 +1478,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
             resolver.resolve(&string_vec!["@out(0)"], None).unwrap();
@@ -1032,7 +1491,8 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
             resolver
@@ -1046,10 +1506,51 @@ mod tests {
             let project_root = get_project_root();
             let workspace_root = get_workspace_root();
             let file_groups = create_file_groups();
-            let metadata = TokenSharedData::new(&file_groups, &workspace_root, &project_root);
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
             let resolver = TokenResolver::for_outputs(&metadata);
 
             resolver.resolve(&string_vec!["$project"], None).unwrap();
         }
+
+        #[test]
+        #[should_panic(expected = "InvalidTokenContext(\"$VAR\", \"outputs\"))")]
+        fn doesnt_support_env_vars() {
+            let project_root = get_project_root();
+            let workspace_root = get_workspace_root();
+            let file_groups = create_file_groups();
+            let metadata =
+                TokenSharedData::new(&file_groups, &workspace_root, &project_root, &None);
+            let resolver = TokenResolver::for_outputs(&metadata);
+
+            resolver.resolve(&string_vec!["$NODE_ENV"], None).unwrap();
+        }
+    }
+
+    mod runtime {
+        use super::*;
+        use chrono::TimeZone;
+
+        #[test]
+        fn resolves_all_vars_in_value() {
+            let data = TokenRuntimeData {
+                branch: "master".to_owned(),
+                hash: "abc123".to_owned(),
+                now: Local.ymd(2022, 6, 1).and_hms(12, 30, 0),
+            };
+
+            assert_eq!(
+                resolve_runtime_vars("$branch@$hash", &data),
+                "master@abc123"
+            );
+            assert_eq!(
+                resolve_runtime_vars("$datetime $date $time", &data),
+                "2022-06-01_12:30:00 2022-06-01 12:30:00"
+            );
+            assert_eq!(
+                resolve_runtime_vars("$project-$date", &data),
+                "$project-2022-06-01"
+            );
+        }
     }
 }
@@ -1,7 +1,7 @@
 use insta::assert_snapshot;
 use moon_cache::CacheEngine;
 use moon_config::GlobalProjectConfig;
-use moon_project::ProjectGraph;
+use moon_project::{DependencySource, ProjectGraph, Target};
 use moon_utils::string_vec;
 use moon_utils::test::get_fixtures_dir;
 use std::collections::HashMap;
@@ -42,6 +42,60 @@ async fn get_dependents_graph() -> ProjectGraph {
     .unwrap()
 }
 
+async fn get_cycle_graph() -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/cycle");
+
+    ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from([
+            ("a".to_owned(), "a".to_owned()),
+            ("b".to_owned(), "b".to_owned()),
+            ("c".to_owned(), "c".to_owned()),
+        ]),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn get_inferred_graph(infer_package: bool, infer_tsconfig: bool) -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/inferred");
+
+    let mut graph = ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from([
+            ("a".to_owned(), "a".to_owned()),
+            ("b".to_owned(), "b".to_owned()),
+            ("c".to_owned(), "c".to_owned()),
+            ("d".to_owned(), "d".to_owned()),
+            ("e".to_owned(), "e".to_owned()),
+        ]),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap();
+
+    graph
+        .set_infer_dependencies(infer_package)
+        .set_infer_tsconfig_dependencies(infer_tsconfig.then(|| "tsconfig.json".to_owned()));
+    graph
+}
+
+async fn get_aliases_graph(fixture: &str, ids: &[&str]) -> ProjectGraph {
+    let workspace_root = get_fixtures_dir(fixture);
+
+    ProjectGraph::create(
+        &workspace_root,
+        GlobalProjectConfig::default(),
+        &HashMap::from_iter(ids.iter().map(|id| (id.to_string(), id.to_string()))),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
 mod get_dependencies_of {
     use super::*;
 
@@ -86,6 +140,70 @@ mod get_dependents_of {
     }
 }
 
+mod get_path_between {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_shortest_path() {
+        let graph = get_dependencies_graph().await;
+
+        assert_eq!(
+            graph.get_path_between("a", "c").unwrap(),
+            Some(string_vec!["a", "b", "c"])
+        );
+        assert_eq!(
+            graph.get_path_between("d", "c").unwrap(),
+            Some(string_vec!["d", "c"])
+        );
+        assert_eq!(
+            graph.get_path_between("a", "a").unwrap(),
+            Some(string_vec!["a"])
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_none_when_not_connected() {
+        let graph = get_dependencies_graph().await;
+
+        assert_eq!(graph.get_path_between("c", "a").unwrap(), None);
+        assert_eq!(graph.get_path_between("a", "d").unwrap(), None);
+    }
+}
+
+mod cycles {
+    use super::*;
+
+    #[tokio::test]
+    #[should_panic(expected = "DependencyCycleDetected(\"a → c → a\"")]
+    async fn detects_minimal_cycle() {
+        let graph = get_cycle_graph().await;
+
+        graph.load("a").unwrap();
+    }
+
+    #[tokio::test]
+    async fn includes_edge_origins() {
+        let graph = get_cycle_graph().await;
+        let error = graph.load("b").unwrap_err().to_string();
+
+        assert!(error.contains(
+            "<id>a</id> → <id>c</id> (<symbol>dependsOn</symbol> entry <id>c</id> in <file>a/project.yml</file>)"
+        ));
+        assert!(error.contains(
+            "<id>c</id> → <id>a</id> (<symbol>dependsOn</symbol> entry <id>a</id> in <file>c/project.yml</file>)"
+        ));
+    }
+
+    #[tokio::test]
+    async fn removes_projects_after_a_failed_load() {
+        let graph = get_cycle_graph().await;
+
+        assert!(graph.load("a").is_err());
+        assert!(graph.load("a").is_err());
+        assert!(graph.load("c").is_err());
+    }
+}
+
 mod to_dot {
     use super::*;
 
@@ -101,3 +219,325 @@ mod to_dot {
         assert_snapshot!(graph.to_dot());
     }
 }
+
+mod inferred_dependencies {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_uses_depends_on_by_default() {
+        let graph = get_inferred_graph(false, false).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_dependency_sources_of(&a).unwrap(),
+            vec![("b".to_owned(), DependencySource::Explicit("b".to_owned()))]
+        );
+    }
+
+    #[tokio::test]
+    async fn infers_from_package_json_dependencies() {
+        let graph = get_inferred_graph(true, false).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_dependency_sources_of(&a).unwrap(),
+            vec![
+                ("b".to_owned(), DependencySource::Explicit("b".to_owned())),
+                (
+                    "c".to_owned(),
+                    DependencySource::Inferred("package.json".to_owned())
+                ),
+                (
+                    "d".to_owned(),
+                    DependencySource::Inferred("package.json".to_owned())
+                ),
+            ]
+        );
+
+        let mut deps = graph.get_dependencies_of(&a).unwrap();
+        deps.sort();
+
+        assert_eq!(deps, string_vec!["b", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn includes_inferred_package_json_dependencies_in_project() {
+        let graph = get_inferred_graph(true, false).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(a.get_dependencies(), string_vec!["b", "c", "d"]);
+        assert_eq!(
+            a.get_task("build")
+                .unwrap()
+                .deps
+                .iter()
+                .map(|dep| dep.target.clone())
+                .collect::<Vec<String>>(),
+            string_vec!["b:build", "c:build", "d:build"]
+        );
+    }
+
+    #[tokio::test]
+    async fn includes_inferred_dependents() {
+        let graph = get_inferred_graph(true, false).await;
+
+        graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_dependents_of(&graph.load("d").unwrap()).unwrap(),
+            string_vec!["a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn infers_from_tsconfig_references_and_paths() {
+        let graph = get_inferred_graph(false, true).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_dependency_sources_of(&a).unwrap(),
+            vec![
+                ("b".to_owned(), DependencySource::Explicit("b".to_owned())),
+                (
+                    "c".to_owned(),
+                    DependencySource::Inferred("tsconfig.json".to_owned())
+                ),
+                (
+                    "e".to_owned(),
+                    DependencySource::Inferred("tsconfig.json".to_owned())
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn includes_inferred_tsconfig_dependencies_in_project() {
+        let graph = get_inferred_graph(false, true).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(a.get_dependencies(), string_vec!["b", "c", "e"]);
+        assert_eq!(
+            a.get_task("build")
+                .unwrap()
+                .deps
+                .iter()
+                .map(|dep| dep.target.clone())
+                .collect::<Vec<String>>(),
+            string_vec!["b:build", "c:build", "e:build"]
+        );
+    }
+
+    #[tokio::test]
+    async fn prefers_package_json_when_inferred_from_both() {
+        let graph = get_inferred_graph(true, true).await;
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            graph.get_dependency_sources_of(&a).unwrap(),
+            vec![
+                ("b".to_owned(), DependencySource::Explicit("b".to_owned())),
+                (
+                    "c".to_owned(),
+                    DependencySource::Inferred("package.json".to_owned())
+                ),
+                (
+                    "d".to_owned(),
+                    DependencySource::Inferred("package.json".to_owned())
+                ),
+                (
+                    "e".to_owned(),
+                    DependencySource::Inferred("tsconfig.json".to_owned())
+                ),
+            ]
+        );
+    }
+}
+
+mod aliases {
+    use super::*;
+
+    #[tokio::test]
+    async fn loads_by_package_name() {
+        let graph = get_aliases_graph("project-graph/aliases", &["one", "two", "three"]).await;
+
+        assert_eq!(graph.load("@scope/one").unwrap().id, "one");
+    }
+
+    #[tokio::test]
+    async fn loads_by_config_alias_and_package_name() {
+        let graph = get_aliases_graph("project-graph/aliases", &["one", "two", "three"]).await;
+
+        assert_eq!(graph.load("@scope/two").unwrap().id, "two");
+        assert_eq!(graph.load("two-pkg").unwrap().id, "two");
+    }
+
+    #[tokio::test]
+    async fn resolves_aliases_in_depends_on() {
+        let graph = get_aliases_graph("project-graph/aliases", &["one", "two", "three"]).await;
+        let three = graph.load("three").unwrap();
+
+        assert_eq!(
+            graph.get_dependencies_of(&three).unwrap(),
+            string_vec!["one"]
+        );
+        assert_eq!(
+            graph.get_dependency_sources_of(&three).unwrap(),
+            vec![(
+                "one".to_owned(),
+                DependencySource::Explicit("@scope/one".to_owned())
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_aliases_in_targets() {
+        let graph = get_aliases_graph("project-graph/aliases", &["one", "two", "three"]).await;
+        let target = graph
+            .resolve_target(&Target::parse("@scope/one:build").unwrap())
+            .unwrap();
+
+        assert_eq!(target.id, "one:build");
+        assert_eq!(target.project_id, Some("one".to_owned()));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "UnconfiguredID(\"@scope/unknown\")")]
+    async fn errors_for_unknown_alias() {
+        let graph = get_aliases_graph("project-graph/aliases", &["one", "two", "three"]).await;
+
+        graph.load("@scope/unknown").unwrap();
+    }
+
+    #[tokio::test]
+    async fn prefers_ids_over_package_names() {
+        let graph =
+            get_aliases_graph("project-graph/aliases", &["one", "two", "three", "four"]).await;
+
+        assert_eq!(graph.load("one").unwrap().id, "one");
+        assert_eq!(graph.load("@scope/one").unwrap().id, "one");
+        assert!(graph.find_alias_collisions().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn doesnt_load_aliases_when_created() {
+        let graph = get_aliases_graph("project-graph/aliases-collision", &["a", "b"]).await;
+
+        assert_eq!(graph.load("a").unwrap().id, "a");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "AliasCollision(\"dupe\", \"a\", \"b\")")]
+    async fn errors_for_alias_collisions_when_resolved() {
+        let graph = get_aliases_graph("project-graph/aliases-collision", &["a", "b"]).await;
+
+        graph.load("dupe").unwrap();
+    }
+
+    #[tokio::test]
+    async fn finds_all_alias_collisions() {
+        let graph = get_aliases_graph("project-graph/aliases-collision", &["a", "b", "c"]).await;
+        let collisions = graph
+            .find_alias_collisions()
+            .unwrap()
+            .iter()
+            .map(|error| format!("{:?}", error))
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            collisions,
+            string_vec![
+                "AliasCollision(\"dupe\", \"a\", \"b\")",
+                "AliasCollision(\"a\", \"a\", \"c\")"
+            ]
+        );
+    }
+}
+
+mod constraints {
+    use super::*;
+    use moon_config::{ConstraintsConfig, ProjectType};
+
+    async fn get_constraints_graph(constraints: ConstraintsConfig) -> ProjectGraph {
+        let mut graph = get_aliases_graph(
+            "project-graph/constraints",
+            &["app", "lib", "mobile", "shared", "tool", "web"],
+        )
+        .await;
+
+        graph.set_constraints(constraints);
+        graph
+    }
+
+    fn create_constraints() -> ConstraintsConfig {
+        ConstraintsConfig {
+            dependency_types: vec![ProjectType::Library, ProjectType::Tool],
+            forbidden_tag_dependencies: HashMap::from([("mobile".to_owned(), string_vec!["web"])]),
+        }
+    }
+
+    #[tokio::test]
+    async fn allows_everything_by_default() {
+        let graph = get_constraints_graph(ConstraintsConfig::default()).await;
+
+        graph.load("lib").unwrap();
+        graph.load("mobile").unwrap();
+    }
+
+    #[tokio::test]
+    async fn allows_valid_dependencies() {
+        let graph = get_constraints_graph(create_constraints()).await;
+
+        graph.load("shared").unwrap();
+        graph.load("web").unwrap();
+    }
+
+    #[tokio::test]
+    async fn errors_for_disallowed_dependency_type() {
+        let graph = get_constraints_graph(create_constraints()).await;
+        let error = graph.load("lib").unwrap_err().to_string();
+
+        assert_eq!(
+            error,
+            "Project <id>lib</id> cannot depend on <id>app</id> (<symbol>dependsOn</symbol> entry <id>app</id> in <file>lib/project.yml</file>), as <symbol>application</symbol> projects may not be dependencies."
+        );
+    }
+
+    #[tokio::test]
+    async fn doesnt_keep_projects_that_failed_validation() {
+        let graph = get_constraints_graph(create_constraints()).await;
+
+        assert!(graph.load("lib").is_err());
+        assert!(graph.load("lib").is_err());
+        assert!(graph.load("app").is_ok());
+    }
+
+    #[tokio::test]
+    async fn errors_for_forbidden_tag() {
+        let graph = get_constraints_graph(create_constraints()).await;
+        let error = graph.load("mobile").unwrap_err().to_string();
+
+        assert_eq!(
+            error,
+            "Project <id>mobile</id> cannot depend on <id>web</id> (<symbol>dependsOn</symbol> entry <id>web</id> in <file>mobile/project.yml</file>), as projects tagged <symbol>mobile</symbol> may not depend on projects tagged <symbol>web</symbol>."
+        );
+    }
+
+    #[tokio::test]
+    async fn finds_all_violations() {
+        let graph = get_constraints_graph(ConstraintsConfig::default()).await;
+
+        for id in graph.ids() {
+            graph.load(&id).unwrap();
+        }
+
+        let violations = graph
+            .find_constraint_violations(&create_constraints())
+            .iter()
+            .map(|error| error.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].starts_with("Project <id>lib</id> cannot depend on <id>app</id>"));
+        assert!(violations[1].starts_with("Project <id>mobile</id> cannot depend on <id>web</id>"));
+    }
+}
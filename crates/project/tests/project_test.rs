@@ -1,9 +1,9 @@
 use moon_config::package::PackageJson;
 use moon_config::{
     GlobalProjectConfig, ProjectConfig, ProjectLanguage, ProjectMetadataConfig, ProjectType,
-    TargetID, TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
+    TargetID, TaskConfig, TaskDependencyConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
 };
-use moon_project::{EnvVars, FileGroup, Project, ProjectError, Target, Task};
+use moon_project::{EnvVars, FileGroup, Project, ProjectError, Target, Task, TaskDependency};
 use moon_utils::string_vec;
 use moon_utils::test::{get_fixtures_dir, get_fixtures_root};
 use std::collections::{BTreeMap, HashMap};
@@ -21,6 +21,8 @@ fn mock_global_project_config() -> GlobalProjectConfig {
         extends: None,
         file_groups: HashMap::from([(String::from("sources"), string_vec!["src/**/*"])]),
         tasks: HashMap::new(),
+        languages: HashMap::new(),
+        types: HashMap::new(),
         schema: String::new(),
     }
 }
@@ -33,6 +35,7 @@ fn doesnt_exist() {
         "projects/missing",
         &get_fixtures_root(),
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
 }
@@ -45,6 +48,7 @@ fn no_config() {
         "projects/no-config",
         &workspace_root,
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
 
@@ -69,6 +73,7 @@ fn empty_config() {
         "projects/empty-config",
         &workspace_root,
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
 
@@ -94,6 +99,7 @@ fn basic_config() {
         "projects/basic",
         &workspace_root,
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
     let project_root = workspace_root.join("projects/basic");
@@ -132,6 +138,7 @@ fn advanced_config() {
         "projects/advanced",
         &workspace_root,
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
 
@@ -170,6 +177,7 @@ fn overrides_global_file_groups() {
             file_groups: HashMap::from([(String::from("tests"), string_vec!["tests/**/*"])]),
             ..GlobalProjectConfig::default()
         },
+        &HashMap::new(),
     )
     .unwrap();
 
@@ -203,6 +211,7 @@ async fn has_package_json() {
         "projects/package-json",
         &workspace_root,
         &mock_global_project_config(),
+        &HashMap::new(),
     )
     .unwrap();
 
@@ -236,11 +245,13 @@ mod tasks {
 
     fn mock_merged_task_options_config(strategy: TaskMergeStrategy) -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
             merge_args: Some(strategy.clone()),
             merge_deps: Some(strategy.clone()),
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            mutex: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
@@ -249,11 +260,13 @@ mod tasks {
 
     fn mock_local_task_options_config(strategy: TaskMergeStrategy) -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
             merge_args: Some(strategy.clone()),
             merge_deps: Some(strategy.clone()),
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            mutex: None,
             retry_count: None,
             run_in_ci: None,
             run_from_workspace_root: None,
@@ -262,11 +275,13 @@ mod tasks {
 
     fn stub_global_task_options_config() -> TaskOptionsConfig {
         TaskOptionsConfig {
+            env_file: None,
             merge_args: None,
             merge_deps: None,
             merge_env: None,
             merge_inputs: None,
             merge_outputs: None,
+            mutex: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
@@ -307,6 +322,7 @@ mod tasks {
                 tasks: HashMap::from([(String::from("standard"), mock_task_config("cmd"))]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -347,6 +363,7 @@ mod tasks {
                 tasks: HashMap::from([(String::from("standard"), mock_task_config("cmd"))]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -418,9 +435,11 @@ mod tasks {
                     TaskConfig {
                         args: Some(string_vec!["--a"]),
                         command: Some(String::from("standard")),
-                        deps: Some(string_vec!["a:standard"]),
+                        deps: Some(vec![TaskDependencyConfig::new("a:standard")]),
                         env: Some(stub_global_env_vars()),
+                        extends: None,
                         inputs: Some(string_vec!["a.*"]),
+                        matrix: None,
                         outputs: Some(string_vec!["a.ts"]),
                         options: stub_global_task_options_config(),
                         type_of: TaskType::Node,
@@ -428,6 +447,7 @@ mod tasks {
                 )]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -441,9 +461,11 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b"]),
                             command: Some(String::from("newcmd")),
-                            deps: Some(string_vec!["b:standard"]),
+                            deps: Some(vec![TaskDependencyConfig::new("b:standard")]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts"]),
                             options: mock_local_task_options_config(TaskMergeStrategy::Replace),
                             type_of: TaskType::System,
@@ -461,9 +483,11 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b"]),
                             command: Some(String::from("newcmd")),
-                            deps: Some(string_vec!["b:standard"]),
+                            deps: Some(vec![TaskDependencyConfig::new("b:standard")]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts"]),
                             options: mock_merged_task_options_config(TaskMergeStrategy::Replace),
                             type_of: TaskType::System,
@@ -492,9 +516,11 @@ mod tasks {
                     TaskConfig {
                         args: Some(string_vec!["--a"]),
                         command: Some(String::from("standard")),
-                        deps: Some(string_vec!["a:standard"]),
+                        deps: Some(vec![TaskDependencyConfig::new("a:standard")]),
                         env: Some(stub_global_env_vars()),
+                        extends: None,
                         inputs: Some(string_vec!["a.*"]),
+                        matrix: None,
                         outputs: Some(string_vec!["a.ts"]),
                         options: stub_global_task_options_config(),
                         type_of: TaskType::Node,
@@ -502,6 +528,7 @@ mod tasks {
                 )]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -515,9 +542,11 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b"]),
                             command: None,
-                            deps: Some(string_vec!["b:standard"]),
+                            deps: Some(vec![TaskDependencyConfig::new("b:standard")]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts"]),
                             options: mock_local_task_options_config(TaskMergeStrategy::Append),
                             type_of: TaskType::System,
@@ -535,12 +564,17 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--a", "--b"]),
                             command: Some(String::from("standard")),
-                            deps: Some(string_vec!["a:standard", "b:standard"]),
+                            deps: Some(vec![
+                                TaskDependencyConfig::new("a:standard"),
+                                TaskDependencyConfig::new("b:standard")
+                            ]),
                             env: Some(HashMap::from([
                                 ("GLOBAL".to_owned(), "1".to_owned()),
                                 ("KEY".to_owned(), "b".to_owned())
                             ])),
+                            extends: None,
                             inputs: Some(string_vec!["a.*", "b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["a.ts", "b.ts"]),
                             options: mock_merged_task_options_config(TaskMergeStrategy::Append),
                             type_of: TaskType::System,
@@ -569,9 +603,11 @@ mod tasks {
                     TaskConfig {
                         args: Some(string_vec!["--a"]),
                         command: Some(String::from("standard")),
-                        deps: Some(string_vec!["a:standard"]),
+                        deps: Some(vec![TaskDependencyConfig::new("a:standard")]),
                         env: Some(stub_global_env_vars()),
+                        extends: None,
                         inputs: Some(string_vec!["a.*"]),
+                        matrix: None,
                         outputs: Some(string_vec!["a.ts"]),
                         options: stub_global_task_options_config(),
                         type_of: TaskType::Node,
@@ -579,6 +615,7 @@ mod tasks {
                 )]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -592,9 +629,11 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b"]),
                             command: Some(String::from("newcmd")),
-                            deps: Some(string_vec!["b:standard"]),
+                            deps: Some(vec![TaskDependencyConfig::new("b:standard")]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts"]),
                             options: mock_local_task_options_config(TaskMergeStrategy::Prepend),
                             type_of: TaskType::System,
@@ -612,12 +651,17 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b", "--a"]),
                             command: Some(String::from("newcmd")),
-                            deps: Some(string_vec!["b:standard", "a:standard"]),
+                            deps: Some(vec![
+                                TaskDependencyConfig::new("b:standard"),
+                                TaskDependencyConfig::new("a:standard")
+                            ]),
                             env: Some(HashMap::from([
                                 ("GLOBAL".to_owned(), "1".to_owned()),
                                 ("KEY".to_owned(), "a".to_owned())
                             ])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*", "a.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts", "a.ts"]),
                             options: mock_merged_task_options_config(TaskMergeStrategy::Prepend),
                             type_of: TaskType::System,
@@ -646,9 +690,11 @@ mod tasks {
                     TaskConfig {
                         args: Some(string_vec!["--a"]),
                         command: Some(String::from("standard")),
-                        deps: Some(string_vec!["a:standard"]),
+                        deps: Some(vec![TaskDependencyConfig::new("a:standard")]),
                         env: Some(stub_global_env_vars()),
+                        extends: None,
                         inputs: Some(string_vec!["a.*"]),
+                        matrix: None,
                         outputs: Some(string_vec!["a.ts"]),
                         options: stub_global_task_options_config(),
                         type_of: TaskType::Node,
@@ -656,6 +702,7 @@ mod tasks {
                 )]),
                 ..GlobalProjectConfig::default()
             },
+            &HashMap::new(),
         )
         .unwrap();
 
@@ -669,16 +716,20 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--b"]),
                             command: None,
-                            deps: Some(string_vec!["b:standard"]),
+                            deps: Some(vec![TaskDependencyConfig::new("b:standard")]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["b.ts"]),
                             options: TaskOptionsConfig {
+                                env_file: None,
                                 merge_args: Some(TaskMergeStrategy::Append),
                                 merge_deps: Some(TaskMergeStrategy::Prepend),
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                mutex: None,
                                 retry_count: None,
                                 run_in_ci: None,
                                 run_from_workspace_root: None,
@@ -698,16 +749,23 @@ mod tasks {
                         TaskConfig {
                             args: Some(string_vec!["--a", "--b"]),
                             command: Some(String::from("standard")),
-                            deps: Some(string_vec!["b:standard", "a:standard"]),
+                            deps: Some(vec![
+                                TaskDependencyConfig::new("b:standard"),
+                                TaskDependencyConfig::new("a:standard")
+                            ]),
                             env: Some(HashMap::from([("KEY".to_owned(), "b".to_owned())])),
+                            extends: None,
                             inputs: Some(string_vec!["b.*"]),
+                            matrix: None,
                             outputs: Some(string_vec!["a.ts", "b.ts"]),
                             options: TaskOptionsConfig {
+                                env_file: None,
                                 merge_args: Some(TaskMergeStrategy::Append),
                                 merge_deps: Some(TaskMergeStrategy::Prepend),
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                mutex: None,
                                 retry_count: Some(1),
                                 run_in_ci: Some(true),
                                 run_from_workspace_root: None,
@@ -735,12 +793,16 @@ mod tasks {
                 "self",
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
             assert_eq!(
                 project.tasks.get("lint").unwrap().deps,
-                string_vec!["id:clean", "id:build"]
+                vec![
+                    TaskDependency::new("id:clean"),
+                    TaskDependency::new("id:build")
+                ]
             );
         }
 
@@ -751,12 +813,13 @@ mod tasks {
                 "self-dupes",
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
             assert_eq!(
                 project.tasks.get("lint").unwrap().deps,
-                string_vec!["id:build"]
+                vec![TaskDependency::new("id:build")]
             );
         }
 
@@ -767,12 +830,17 @@ mod tasks {
                 "deps",
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
             assert_eq!(
                 project.tasks.get("build").unwrap().deps,
-                string_vec!["foo:build", "bar:build", "baz:build"]
+                vec![
+                    TaskDependency::new("foo:build"),
+                    TaskDependency::new("bar:build"),
+                    TaskDependency::new("baz:build")
+                ]
             );
         }
 
@@ -783,12 +851,71 @@ mod tasks {
                 "deps-dupes",
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                project.tasks.get("build").unwrap().deps,
+                vec![
+                    TaskDependency::new("foo:build"),
+                    TaskDependency::new("bar:build"),
+                    TaskDependency::new("baz:build")
+                ]
+            );
+        }
+
+        #[test]
+        fn merges_dependency_kinds_when_resolving_scopes() {
+            let project = Project::new(
+                "id",
+                "kinds",
+                &get_fixtures_dir("task-deps"),
+                &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
             assert_eq!(
                 project.tasks.get("build").unwrap().deps,
-                string_vec!["foo:build", "bar:build", "baz:build"]
+                vec![
+                    TaskDependency::new("foo:build"),
+                    TaskDependency {
+                        optional: true,
+                        ..TaskDependency::new("bar:build")
+                    },
+                    TaskDependency {
+                        after: true,
+                        ..TaskDependency::new("id:lint")
+                    }
+                ]
+            );
+        }
+
+        #[test]
+        fn preserves_overrides_when_resolving_scopes() {
+            let project = Project::new(
+                "id",
+                "overrides",
+                &get_fixtures_dir("task-deps"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let create_dep = |target: &str| TaskDependency {
+                args: string_vec!["--mode", "production"],
+                env: HashMap::from([("NODE_ENV".to_owned(), "production".to_owned())]),
+                ..TaskDependency::new(target)
+            };
+
+            assert_eq!(
+                project.tasks.get("build").unwrap().deps,
+                vec![
+                    create_dep("foo:codegen"),
+                    create_dep("bar:codegen"),
+                    TaskDependency::new("id:clean")
+                ]
             );
         }
 
@@ -800,6 +927,7 @@ mod tasks {
                 "all",
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
         }
@@ -838,6 +966,7 @@ mod tasks {
                     )]),
                     ..GlobalProjectConfig::default()
                 },
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -910,6 +1039,7 @@ mod tasks {
                     )]),
                     ..GlobalProjectConfig::default()
                 },
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -965,6 +1095,7 @@ mod tasks {
                     )]),
                     ..GlobalProjectConfig::default()
                 },
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1019,6 +1150,7 @@ mod tasks {
                     )]),
                     ..GlobalProjectConfig::default()
                 },
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1051,6 +1183,192 @@ mod tasks {
             assert_eq!(a, b);
         }
     }
+
+    mod matrix {
+        use super::*;
+
+        #[test]
+        fn creates_a_variant_per_combination() {
+            let project = Project::new(
+                "id",
+                "matrix",
+                &get_fixtures_dir("tasks"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                project.tasks.get("unit").unwrap().variants,
+                string_vec![
+                    "unit[env=node,shard=1]",
+                    "unit[env=node,shard=2]",
+                    "unit[env=jsdom,shard=1]",
+                    "unit[env=jsdom,shard=2]"
+                ]
+            );
+            assert_eq!(project.tasks.len(), 5);
+        }
+
+        #[test]
+        fn substitutes_values_into_args_and_env() {
+            let project = Project::new(
+                "id",
+                "matrix",
+                &get_fixtures_dir("tasks"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let variant = project.get_task("unit[env=jsdom,shard=2]").unwrap();
+
+            assert_eq!(variant.target, "id:unit[env=jsdom,shard=2]");
+            assert_eq!(variant.args, string_vec!["--env", "jsdom"]);
+            assert_eq!(variant.env.get("NODE_ENV").unwrap(), "jsdom");
+            assert_eq!(
+                variant.matrix_values,
+                BTreeMap::from([
+                    ("env".to_owned(), "jsdom".to_owned()),
+                    ("shard".to_owned(), "2".to_owned())
+                ])
+            );
+            assert!(variant.matrix.is_empty());
+            assert!(variant.variants.is_empty());
+        }
+    }
+
+    mod extends {
+        use super::*;
+
+        #[test]
+        fn inherits_and_merges_extended_task() {
+            let project = Project::new(
+                "id",
+                "basic",
+                &get_fixtures_dir("task-extends"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let task = project.get_task("test-ci").unwrap();
+
+            assert_eq!(task.target, "id:test-ci");
+            assert_eq!(task.command, "jest");
+            assert_eq!(task.args, string_vec!["--color", "--ci"]);
+            assert_eq!(
+                task.env,
+                HashMap::from([
+                    ("NODE_ENV".to_owned(), "test".to_owned()),
+                    ("CI".to_owned(), "true".to_owned())
+                ])
+            );
+        }
+
+        #[test]
+        fn uses_merge_strategy_options() {
+            let project = Project::new(
+                "id",
+                "basic",
+                &get_fixtures_dir("task-extends"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let task = project.get_task("test-watch").unwrap();
+
+            assert_eq!(task.args, string_vec!["--watch"]);
+            assert!(!task.options.run_in_ci);
+        }
+
+        #[test]
+        fn resolves_chained_extends() {
+            let project = Project::new(
+                "id",
+                "basic",
+                &get_fixtures_dir("task-extends"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let task = project.get_task("test-ci-coverage").unwrap();
+
+            assert_eq!(task.target, "id:test-ci-coverage");
+            assert_eq!(task.args, string_vec!["--color", "--ci", "--coverage"]);
+
+            // Extended task is unchanged
+            assert_eq!(
+                project.get_task("test").unwrap().args,
+                string_vec!["--color"]
+            );
+        }
+
+        #[test]
+        fn extends_global_tasks() {
+            let project = Project::new(
+                "id",
+                "global",
+                &get_fixtures_dir("task-extends"),
+                &GlobalProjectConfig {
+                    tasks: HashMap::from([
+                        (
+                            "lint-base".to_owned(),
+                            TaskConfig {
+                                command: Some("eslint".to_owned()),
+                                args: Some(string_vec!["."]),
+                                ..TaskConfig::default()
+                            },
+                        ),
+                        (
+                            "lint".to_owned(),
+                            TaskConfig {
+                                extends: Some("lint-base".to_owned()),
+                                args: Some(string_vec!["--fix"]),
+                                ..TaskConfig::default()
+                            },
+                        ),
+                    ]),
+                    ..GlobalProjectConfig::default()
+                },
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            let task = project.get_task("lint").unwrap();
+
+            assert_eq!(task.command, "eslint");
+            assert_eq!(task.args, string_vec![".", "--fix", "--cache"]);
+        }
+
+        #[test]
+        #[should_panic(expected = "TaskExtendsCycle(\"a</id> → <id>c</id> → <id>b</id> → <id>a\")")]
+        fn errors_for_cycles() {
+            Project::new(
+                "id",
+                "cycle",
+                &get_fixtures_dir("task-extends"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "UnknownExtendedTask(\"test\", \"missing\")")]
+        fn errors_for_unknown_task() {
+            Project::new(
+                "id",
+                "unknown",
+                &get_fixtures_dir("task-extends"),
+                &mock_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+        }
+    }
 }
 
 mod workspace {
@@ -1103,6 +1421,7 @@ mod workspace {
                 "include",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1116,6 +1435,7 @@ mod workspace {
                 "include-none",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1129,6 +1449,7 @@ mod workspace {
                 "exclude",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1142,6 +1463,7 @@ mod workspace {
                 "exclude-all",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1155,6 +1477,7 @@ mod workspace {
                 "exclude-none",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1168,6 +1491,7 @@ mod workspace {
                 "rename",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1185,6 +1509,7 @@ mod workspace {
                 "rename-merge",
                 &workspace_root,
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1208,6 +1533,7 @@ mod workspace {
                 "include-exclude",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
@@ -1221,10 +1547,166 @@ mod workspace {
                 "include-exclude-rename",
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
+                &HashMap::new(),
             )
             .unwrap();
 
             assert_eq!(get_project_task_ids(project), string_vec!["only"])
         }
+
+        fn mock_scoped_global_project_config() -> GlobalProjectConfig {
+            let scoped_task = |command: &str| GlobalProjectConfig {
+                tasks: HashMap::from([(
+                    command.to_owned(),
+                    TaskConfig {
+                        command: Some(command.to_owned()),
+                        ..TaskConfig::default()
+                    },
+                )]),
+                ..GlobalProjectConfig::default()
+            };
+
+            GlobalProjectConfig {
+                languages: HashMap::from([
+                    (ProjectLanguage::JavaScript, scoped_task("js")),
+                    (ProjectLanguage::TypeScript, scoped_task("ts")),
+                ]),
+                types: HashMap::from([
+                    (ProjectType::Application, scoped_task("app")),
+                    (ProjectType::Library, scoped_task("lib")),
+                ]),
+                ..mock_global_project_config()
+            }
+        }
+
+        #[test]
+        fn scoped_by_language_and_type() {
+            let project = Project::new(
+                "id",
+                "scoped",
+                &get_fixtures_dir("task-inheritance"),
+                &mock_scoped_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                get_project_task_ids(project),
+                string_vec!["a", "app", "b", "c", "js"]
+            )
+        }
+
+        #[test]
+        fn scoped_by_default_language_and_type() {
+            let project = Project::new(
+                "id",
+                "exclude-none",
+                &get_fixtures_dir("task-inheritance"),
+                &mock_scoped_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                get_project_task_ids(project),
+                string_vec!["a", "b", "c", "lib", "ts"]
+            )
+        }
+
+        #[test]
+        fn scoped_tasks_respect_inheritance_config() {
+            let project = Project::new(
+                "id",
+                "include",
+                &get_fixtures_dir("task-inheritance"),
+                &mock_scoped_global_project_config(),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(get_project_task_ids(project), string_vec!["a", "c"])
+        }
+    }
+}
+
+mod file_group_refs {
+    use super::*;
+
+    fn get_projects() -> HashMap<String, String> {
+        HashMap::from([
+            (String::from("app"), String::from("projects/group-refs/app")),
+            (String::from("lib"), String::from("projects/group-refs/lib")),
+            (
+                String::from("cycleA"),
+                String::from("projects/group-refs/cycle-a"),
+            ),
+            (
+                String::from("cycleB"),
+                String::from("projects/group-refs/cycle-b"),
+            ),
+        ])
+    }
+
+    #[test]
+    fn inlines_referenced_group_files() {
+        let project = Project::new(
+            "app",
+            "projects/group-refs/app",
+            &get_fixtures_root(),
+            &mock_global_project_config(),
+            &get_projects(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            project.file_groups.get("sources").unwrap().files,
+            string_vec![
+                "src/**/*",
+                "/projects/group-refs/lib/src/**/*",
+                "!/projects/group-refs/lib/src/**/*.test.ts",
+                "/tsconfig.json",
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "UnconfiguredID(\"missing\")")]
+    fn errors_for_unknown_project() {
+        Project::new(
+            "unknownProject",
+            "projects/group-refs/unknown-project",
+            &get_fixtures_root(),
+            &mock_global_project_config(),
+            &get_projects(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "UnknownFileGroup(\"@group(lib:missing)\", \"missing\")")]
+    fn errors_for_unknown_group() {
+        Project::new(
+            "unknownGroup",
+            "projects/group-refs/unknown-group",
+            &get_fixtures_root(),
+            &mock_global_project_config(),
+            &get_projects(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "FileGroupReferenceCycle(\"cycleA:sources</id> → <id>cycleB:sources</id> → <id>cycleA:sources\")"
+    )]
+    fn errors_for_cycles() {
+        Project::new(
+            "cycleA",
+            "projects/group-refs/cycle-a",
+            &get_fixtures_root(),
+            &mock_global_project_config(),
+            &get_projects(),
+        )
+        .unwrap();
     }
 }
@@ -0,0 +1,243 @@
+use moon_error::{map_io_to_fs_error, MoonError};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+pub type DotEnvVars = HashMap<String, String>;
+
+#[derive(Debug, PartialEq)]
+pub struct DotEnvError {
+    pub line: usize,
+    pub message: String,
+}
+
+fn create_error(line: usize, message: &str) -> DotEnvError {
+    DotEnvError {
+        line,
+        message: String::from(message),
+    }
+}
+
+fn is_var_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Replace `$VAR` and `${VAR}` references with values from previously
+/// parsed variables, falling back to the current process environment.
+fn interpolate(value: &str, vars: &DotEnvVars) -> String {
+    let lookup = |name: &str| -> String {
+        vars.get(name)
+            .cloned()
+            .or_else(|| env::var(name).ok())
+            .unwrap_or_default()
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&'$') {
+            result.push(chars.next().unwrap());
+            continue;
+        }
+
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+
+            let mut name = String::new();
+            let mut closed = false;
+
+            for n in chars.by_ref() {
+                if n == '}' {
+                    closed = true;
+                    break;
+                }
+
+                name.push(n);
+            }
+
+            if closed {
+                result.push_str(&lookup(&name));
+            } else {
+                result.push_str("${");
+                result.push_str(&name);
+            }
+
+            continue;
+        }
+
+        let mut name = String::new();
+
+        while let Some(n) = chars.peek() {
+            if !is_var_char(*n) {
+                break;
+            }
+
+            name.push(*n);
+            chars.next();
+        }
+
+        if name.is_empty() {
+            result.push('$');
+        } else {
+            result.push_str(&lookup(&name));
+        }
+    }
+
+    result
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('"') => result.push('"'),
+            Some('\\') => result.push('\\'),
+            // Keep escaped `$` so that interpolation can skip it
+            Some(n) => {
+                result.push('\\');
+                result.push(n);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Parse the contents of a dotenv file into a map of variables.
+/// Supports comments, an optional `export` prefix, single quoted (literal) values,
+/// double quoted (escaped) values, and `$VAR`/`${VAR}` interpolation.
+pub fn parse(content: &str) -> Result<DotEnvVars, DotEnvError> {
+    let mut vars = DotEnvVars::new();
+
+    for (index, raw_line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let mut line = raw_line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("export ") {
+            line = rest.trim_start();
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => return Err(create_error(line_no, "Expected a KEY=value pair.")),
+        };
+
+        if key.is_empty() || !key.chars().all(is_var_char) {
+            return Err(create_error(line_no, "Invalid variable name."));
+        }
+
+        let value = if let Some(inner) = value.strip_prefix('\'') {
+            match inner.find('\'') {
+                Some(end) => inner[0..end].to_owned(),
+                None => return Err(create_error(line_no, "Unterminated single quote.")),
+            }
+        } else if let Some(inner) = value.strip_prefix('"') {
+            let mut end = None;
+            let mut escaped = false;
+
+            for (i, c) in inner.char_indices() {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    end = Some(i);
+                    break;
+                }
+            }
+
+            match end {
+                Some(end) => interpolate(&unescape(&inner[0..end]), &vars),
+                None => return Err(create_error(line_no, "Unterminated double quote.")),
+            }
+        } else {
+            // Strip trailing inline comments from unquoted values
+            let unquoted = match value.find(" #") {
+                Some(i) => value[0..i].trim_end(),
+                None => value,
+            };
+
+            interpolate(unquoted, &vars)
+        };
+
+        vars.insert(key.to_owned(), value);
+    }
+
+    Ok(vars)
+}
+
+/// Read and parse a dotenv file at the provided path.
+pub fn load(path: &Path) -> Result<DotEnvVars, MoonError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| map_io_to_fs_error(e, path.to_path_buf()))?;
+
+    parse(&content).map_err(|e| MoonError::DotEnv(path.to_path_buf(), e.line, e.message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_basic_pairs() {
+        let vars = parse("FOO=bar\n# comment\n\nBAZ = qux # trailing\nexport QUX=1").unwrap();
+
+        assert_eq!(vars.get("FOO").unwrap(), "bar");
+        assert_eq!(vars.get("BAZ").unwrap(), "qux");
+        assert_eq!(vars.get("QUX").unwrap(), "1");
+    }
+
+    #[test]
+    fn handles_quotes() {
+        let vars = parse("A='$B literal'\nB=\"line\\nbreak\"\nC=\"has # hash\"").unwrap();
+
+        assert_eq!(vars.get("A").unwrap(), "$B literal");
+        assert_eq!(vars.get("B").unwrap(), "line\nbreak");
+        assert_eq!(vars.get("C").unwrap(), "has # hash");
+    }
+
+    #[test]
+    fn interpolates_previous_vars() {
+        let vars =
+            parse("HOST=localhost\nPORT=3000\nURL=http://${HOST}:$PORT/\nRAW=\\$HOST").unwrap();
+
+        assert_eq!(vars.get("URL").unwrap(), "http://localhost:3000/");
+        assert_eq!(vars.get("RAW").unwrap(), "$HOST");
+    }
+
+    #[test]
+    fn interpolates_unknown_as_empty() {
+        let vars = parse("A=${MOON_DOTENV_UNKNOWN_VAR}value").unwrap();
+
+        assert_eq!(vars.get("A").unwrap(), "value");
+    }
+
+    #[test]
+    fn errors_for_invalid_lines() {
+        assert_eq!(parse("FOO").unwrap_err().line, 1);
+        assert_eq!(parse("A=1\nB C=2").unwrap_err().line, 2);
+        assert_eq!(parse("A='open").unwrap_err().line, 1);
+    }
+}
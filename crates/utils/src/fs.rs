@@ -143,6 +143,21 @@ where
     Ok(json)
 }
 
+/// Synchronous variant of `read_json`, for contexts that can not await,
+/// like the project graph.
+pub fn read_json_sync<T>(path: &Path) -> Result<T, MoonError>
+where
+    T: DeserializeOwned,
+{
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| map_io_to_fs_error(e, path.to_path_buf()))?;
+
+    let json: T = serde_json::from_str(&clean_json(contents)?)
+        .map_err(|e| map_json_to_error(e, path.to_path_buf()))?;
+
+    Ok(json)
+}
+
 pub async fn read_json_string(path: &Path) -> Result<String, MoonError> {
     let json = fs::read_to_string(path)
         .await
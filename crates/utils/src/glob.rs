@@ -14,22 +14,41 @@ pub type GlobError = WaxGlobError<'static>;
 
 pub struct GlobSet<'t> {
     any: Any<'t>,
+
+    negations: Option<Any<'t>>,
 }
 
 impl<'t> GlobSet<'t> {
+    /// Create a set from a list of patterns, where patterns prefixed
+    /// with "!" will exclude paths matched by other patterns.
     pub fn new(patterns: &'t [String]) -> Result<Self, GlobError> {
         let mut globs = vec![];
+        let mut negations = vec![];
 
         for pattern in patterns {
-            globs.push(create_glob(pattern)?);
+            match pattern.strip_prefix('!') {
+                Some(negated) => negations.push(create_glob(negated)?),
+                None => globs.push(create_glob(pattern)?),
+            };
         }
 
         Ok(GlobSet {
             any: wax::any::<Glob, _>(globs).unwrap(),
+            negations: if negations.is_empty() {
+                None
+            } else {
+                Some(wax::any::<Glob, _>(negations).unwrap())
+            },
         })
     }
 
     pub fn matches(&self, path: &Path) -> Result<bool, MoonError> {
+        if let Some(negations) = &self.negations {
+            if negations.is_match(path) {
+                return Ok(false);
+            }
+        }
+
         Ok(self.any.is_match(path))
     }
 }
@@ -98,6 +117,11 @@ pub fn normalize(path: &Path) -> Result<String, MoonError> {
     Ok(glob)
 }
 
+/// Match a single identifier (project ID, task ID, etc) against a glob pattern.
+pub fn matches_id(pattern: &str, id: &str) -> Result<bool, GlobError> {
+    Ok(create_glob(pattern)?.is_match(Path::new(id)))
+}
+
 /// Wax currently doesn't support negated globs (starts with !),
 /// so we must extract them manually.
 pub fn split_patterns(patterns: &[String]) -> Result<(Vec<Glob>, Vec<Glob>), GlobError> {
@@ -180,6 +204,42 @@ mod tests {
         }
     }
 
+    mod glob_set {
+        use super::*;
+
+        #[test]
+        fn matches_patterns() {
+            let patterns = crate::string_vec!["src/**/*.ts", "tests/*"];
+            let set = GlobSet::new(&patterns).unwrap();
+
+            assert!(set.matches(Path::new("src/index.ts")).unwrap());
+            assert!(set.matches(Path::new("tests/file.js")).unwrap());
+            assert!(!set.matches(Path::new("src/index.js")).unwrap());
+        }
+
+        #[test]
+        fn excludes_negated_patterns() {
+            let patterns = crate::string_vec!["src/**/*.ts", "!**/*.test.ts"];
+            let set = GlobSet::new(&patterns).unwrap();
+
+            assert!(set.matches(Path::new("src/index.ts")).unwrap());
+            assert!(!set.matches(Path::new("src/index.test.ts")).unwrap());
+        }
+    }
+
+    mod matches_id {
+        use super::*;
+
+        #[test]
+        fn matches_wildcards() {
+            assert!(matches_id("*", "app").unwrap());
+            assert!(matches_id("web-*", "web-app").unwrap());
+            assert!(matches_id("test-?", "test-a").unwrap());
+            assert!(!matches_id("web-*", "app").unwrap());
+            assert!(!matches_id("test-?", "test-ab").unwrap());
+        }
+    }
+
     mod windows_prefix {
         use super::*;
 
@@ -1,4 +1,5 @@
 use lazy_static::lazy_static;
+pub use regex::Captures;
 use regex::Regex;
 
 lazy_static! {
@@ -7,25 +8,58 @@ lazy_static! {
     static ref ID_CLEAN: Regex = Regex::new("[^a-z0-9_-]+").unwrap();
 
     pub static ref ID_PATTERN: Regex = Regex::new(&format!("^{}$", *ID_GROUP)).unwrap();
+
+    // Project alias, either an ID or a scoped package name: `ui`, `@acme/ui`
+    static ref ALIAS_PATTERN: Regex = Regex::new(
+        "^(?:[A-Za-z]{1}[0-9A-Za-z_-]*|@[0-9A-Za-z_.-]+/[0-9A-Za-z_.-]+)$").unwrap();
+
+    // Tasks may be suffixed with matrix values: `app:test[node=16]`,
+    // and projects may be referenced by alias: `@acme/ui:build`
     pub static ref TARGET_PATTERN: Regex = Regex::new(
-        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*)$").unwrap();
+        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|@[0-9A-Za-z_.-]+/[0-9A-Za-z_.-]+|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*(?:\\[[0-9A-Za-z_.=,-]+\\])?)$").unwrap();
+
+    // Target selector with wildcards and tags: `app:*`, `web-*:build`, `*:test-*`, `#tag:lint`
+    pub static ref TARGET_SELECTOR_PATTERN: Regex = Regex::new(
+        "^(?P<project>(?:[A-Za-z*?]{1}[0-9A-Za-z_*?-]*|@[0-9A-Za-z_.-]+/[0-9A-Za-z_.-]+|#[A-Za-z]{1}[0-9A-Za-z_-]*|\\^|~))?:(?P<task>[A-Za-z*?]{1}[0-9A-Za-z_*?-]*(?:\\[[0-9A-Za-z_.=,-]+\\])?)$").unwrap();
+
+    // Matrix value: `16`, `chrome`, `1.2.3`
+    static ref MATRIX_VALUE_PATTERN: Regex = Regex::new("^[0-9A-Za-z_.-]+$").unwrap();
 
     // Token function: `@func(arg)`
     static ref TOKEN_GROUP: &'static str = "([0-9A-Za-z_-]+)";
 
     pub static ref TOKEN_FUNC_PATTERN: Regex = Regex::new(&format!("^@([a-z]+)\\({}\\)$", *TOKEN_GROUP)).unwrap();
     pub static ref TOKEN_FUNC_ANYWHERE_PATTERN: Regex = Regex::new(&format!("@([a-z]+)\\({}\\)", *TOKEN_GROUP)).unwrap();
-    pub static ref TOKEN_VAR_PATTERN: Regex = Regex::new("\\$([a-zA-Z]+)").unwrap();
+    pub static ref TOKEN_VAR_PATTERN: Regex = Regex::new("\\$(matrix\\.[0-9A-Za-z_-]+|[a-zA-Z]+)").unwrap();
+
+    // File group reference to a group in another project: `@group(project:groupName)`
+    pub static ref FILE_GROUP_REF_PATTERN: Regex = Regex::new(
+        "^@group\\(([A-Za-z]{1}[0-9A-Za-z_-]*):([A-Za-z]{1}[0-9A-Za-z_-]*)\\)$").unwrap();
+
+    // Environment variable: `$VAR_NAME`
+    pub static ref ENV_VAR_PATTERN: Regex = Regex::new("^\\$([A-Z_]{1}[0-9A-Z_]*)$").unwrap();
 }
 
 pub fn clean_id(id: &str) -> String {
     ID_CLEAN.replace(id, "").to_string()
 }
 
+pub fn matches_alias(alias: &str) -> bool {
+    ALIAS_PATTERN.is_match(alias)
+}
+
+pub fn matches_env_var(value: &str) -> bool {
+    ENV_VAR_PATTERN.is_match(value)
+}
+
 pub fn matches_id(id: &str) -> bool {
     ID_PATTERN.is_match(id)
 }
 
+pub fn matches_matrix_value(value: &str) -> bool {
+    MATRIX_VALUE_PATTERN.is_match(value)
+}
+
 pub fn matches_target(target_id: &str) -> bool {
     TARGET_PATTERN.is_match(target_id)
 }
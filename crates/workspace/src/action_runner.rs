@@ -4,18 +4,60 @@ use crate::dep_graph::{DepGraph, Node};
 use crate::errors::WorkspaceError;
 use crate::workspace::Workspace;
 use moon_logger::{color, debug, error, trace};
-use std::sync::Arc;
+use moon_project::{Target, TargetID, TaskDependency};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock};
 use tokio::task;
 
 const LOG_TARGET: &str = "moon:action-runner";
 
+type MutexResources = Arc<StdMutex<HashMap<String, Arc<Mutex<()>>>>>;
+
+/// Acquire a lock for every `mutex` resource the target's task has declared,
+/// so that no other action holding the same resource can run concurrently.
+/// Locks are acquired in sorted order to avoid deadlocks.
+async fn acquire_mutex_resources(
+    workspace: &Arc<RwLock<Workspace>>,
+    resources: &MutexResources,
+    target_id: &str,
+) -> Result<Vec<OwnedMutexGuard<()>>, WorkspaceError> {
+    let mut names = {
+        let workspace = workspace.read().await;
+        let (project_id, task_id) = Target::parse(target_id)?.ids()?;
+        let project = workspace.projects.load(&project_id)?;
+
+        project.get_task(&task_id)?.options.mutex.clone()
+    };
+
+    names.sort();
+    names.dedup();
+
+    let mut guards = vec![];
+
+    for name in names {
+        let lock = Arc::clone(resources.lock().unwrap().entry(name.clone()).or_default());
+
+        trace!(
+            target: LOG_TARGET,
+            "Acquiring mutex {} for {}",
+            color::id(&name),
+            color::target(target_id)
+        );
+
+        guards.push(lock.lock_owned().await);
+    }
+
+    Ok(guards)
+}
+
 async fn run_action(
     workspace: Arc<RwLock<Workspace>>,
     action: &mut Action,
     action_node: &Node,
-    primary_target: &str,
+    overrides: Option<&TaskDependency>,
+    primary_targets: &HashSet<TargetID>,
     passthrough_args: &[String],
 ) -> Result<(), WorkspaceError> {
     let result = match action_node {
@@ -25,7 +67,8 @@ async fn run_action(
                 workspace,
                 action,
                 target_id,
-                primary_target,
+                overrides,
+                primary_targets,
                 passthrough_args,
             )
             .await
@@ -60,7 +103,7 @@ pub struct ActionRunner {
 
     passthrough_args: Vec<String>,
 
-    primary_target: String,
+    primary_targets: HashSet<TargetID>,
 
     workspace: Arc<RwLock<Workspace>>,
 }
@@ -73,7 +116,7 @@ impl ActionRunner {
             bail: false,
             duration: None,
             passthrough_args: Vec::new(),
-            primary_target: String::new(),
+            primary_targets: HashSet::new(),
             workspace: Arc::new(RwLock::new(workspace)),
         }
     }
@@ -101,7 +144,8 @@ impl ActionRunner {
         let batches_count = batches.len();
         let graph = Arc::new(RwLock::new(graph));
         let passthrough_args = Arc::new(self.passthrough_args.clone());
-        let primary_target = Arc::new(self.primary_target.clone());
+        let primary_targets = Arc::new(self.primary_targets.clone());
+        let mutex_resources: MutexResources = Arc::new(StdMutex::new(HashMap::new()));
 
         // Clean the runner state *before* running actions instead of after,
         // so that failing or broken builds can dig into and debug the state!
@@ -132,10 +176,42 @@ impl ActionRunner {
                 let workspace_clone = Arc::clone(&self.workspace);
                 let graph_clone = Arc::clone(&graph);
                 let passthrough_args_clone = Arc::clone(&passthrough_args);
-                let primary_target_clone = Arc::clone(&primary_target);
+                let primary_targets_clone = Arc::clone(&primary_targets);
+                let mutex_resources_clone = Arc::clone(&mutex_resources);
 
                 action_handles.push(task::spawn(async move {
                     let mut action = Action::new(node_index);
+
+                    // Acquire mutex resources before reading the graph, so that actions
+                    // waiting on a resource don't hold the graph lock. Dependencies with
+                    // overrides run (and lock) the original target's task.
+                    let mutex_target_id = {
+                        let own_graph = graph_clone.read().await;
+
+                        match own_graph.get_node_from_index(node_index) {
+                            Some(Node::RunTarget(target_id)) => Some(
+                                own_graph
+                                    .target_overrides
+                                    .get(target_id)
+                                    .map(|dep| dep.target.clone())
+                                    .unwrap_or_else(|| target_id.clone()),
+                            ),
+                            _ => None,
+                        }
+                    };
+
+                    let guards = match &mutex_target_id {
+                        Some(target_id) => {
+                            acquire_mutex_resources(
+                                &workspace_clone,
+                                &mutex_resources_clone,
+                                target_id,
+                            )
+                            .await
+                        }
+                        None => Ok(vec![]),
+                    };
+
                     let own_graph = graph_clone.read().await;
 
                     if let Some(node) = own_graph.get_node_from_index(node_index) {
@@ -151,14 +227,30 @@ impl ActionRunner {
                             log_action_label
                         );
 
-                        run_action(
-                            workspace_clone,
-                            &mut action,
-                            node,
-                            &primary_target_clone,
-                            &passthrough_args_clone,
-                        )
-                        .await?;
+                        // Dependencies with overrides run the original target's task
+                        let overrides = match node {
+                            Node::RunTarget(target_id) => own_graph.target_overrides.get(target_id),
+                            _ => None,
+                        };
+
+                        // Guards are held until the action has completed. Failing to
+                        // acquire them fails the action, instead of the entire batch.
+                        match guards {
+                            Ok(_guards) => {
+                                run_action(
+                                    workspace_clone,
+                                    &mut action,
+                                    node,
+                                    overrides,
+                                    &primary_targets_clone,
+                                    &passthrough_args_clone,
+                                )
+                                .await?;
+                            }
+                            Err(error) => {
+                                action.fail(error.to_string());
+                            }
+                        };
 
                         if action.has_failed() {
                             trace!(
@@ -230,8 +322,8 @@ impl ActionRunner {
         self
     }
 
-    pub fn set_primary_target(&mut self, target: &str) -> &mut Self {
-        self.primary_target = target.to_owned();
+    pub fn set_primary_targets(&mut self, targets: HashSet<TargetID>) -> &mut Self {
+        self.primary_targets = targets;
         self
     }
 }
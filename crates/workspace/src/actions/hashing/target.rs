@@ -1,7 +1,9 @@
+use crate::actions::run_target::create_token_runtime_data;
 use crate::{Workspace, WorkspaceError};
 use moon_hasher::TargetHasher;
-use moon_project::{ExpandedFiles, Project, Task};
+use moon_project::{ExpandedFiles, Project, Task, VOLATILE_TOKEN_VARS};
 use moon_utils::path::path_to_string;
+use std::collections::BTreeSet;
 use std::path::Path;
 
 fn convert_paths_to_strings(
@@ -42,6 +44,28 @@ pub async fn create_target_hasher(
     hasher.hash_task(task);
     hasher.hash_args(passthrough_args);
 
+    // Runtime token vars are resolved after hashing, so hash their values instead,
+    // excluding volatile vars (dates and times) so that they don't bust the cache
+    let token_vars = task
+        .get_runtime_vars()
+        .into_iter()
+        .filter(|var| !VOLATILE_TOKEN_VARS.contains(&var.as_str()))
+        .collect::<BTreeSet<String>>();
+
+    if !token_vars.is_empty() {
+        let data = create_token_runtime_data(workspace, &token_vars).await?;
+
+        hasher.hash_token_vars(
+            token_vars
+                .into_iter()
+                .map(|var| {
+                    let value = data.get_var(&var).unwrap_or_default();
+                    (var, value)
+                })
+                .collect(),
+        );
+    }
+
     // Hash root configs first
     hasher.hash_package_json(&workspace.package_json);
 
@@ -61,6 +85,11 @@ pub async fn create_target_hasher(
         hasher.hash_tsconfig_json(&tsconfig);
     }
 
+    // For input env vars, hash their current values
+    if !task.input_vars.is_empty() {
+        hasher.hash_input_env_vars(task.get_input_env_vars());
+    }
+
     // For input files, hash them with the vcs layer first
     if !task.input_paths.is_empty() {
         let files = convert_paths_to_strings(&task.input_paths, &workspace.root)?;
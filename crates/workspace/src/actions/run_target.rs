@@ -2,16 +2,20 @@ use crate::action::{Action, ActionStatus, Attempt};
 use crate::actions::hashing::create_target_hasher;
 use crate::errors::WorkspaceError;
 use crate::workspace::Workspace;
+use chrono::Local;
+use ignore::WalkBuilder;
 use moon_cache::RunTargetState;
 use moon_config::TaskType;
 use moon_logger::{color, debug, trace, warn};
-use moon_project::{Project, Target, Task};
+use moon_project::{
+    Project, ProjectError, Target, TargetID, Task, TaskDependency, TokenRuntimeData,
+};
 use moon_terminal::output::{label_checkpoint, Checkpoint};
 use moon_toolchain::{get_path_env_var, Executable};
 use moon_utils::process::{join_args, output_to_string, Command, Output};
-use moon_utils::{is_ci, is_test_env, path, string_vec, time};
-use std::collections::HashMap;
-use std::path::Path;
+use moon_utils::{glob, is_ci, is_test_env, path, string_vec, time};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -65,6 +69,92 @@ async fn create_env_vars(
     Ok(env_vars)
 }
 
+/// Take a snapshot of all files within the project root, so that we can detect
+/// files that were created by a task. Hidden and `.gitignore`d files are skipped,
+/// as they're typically internal caches and not outputs.
+async fn snapshot_project_root(project: &Project) -> Result<HashSet<PathBuf>, WorkspaceError> {
+    let mut files = HashSet::new();
+
+    for entry in WalkBuilder::new(&project.root).require_git(false).build() {
+        let entry = entry.map_err(WorkspaceError::Ignore)?;
+
+        if entry.file_type().map(|t| t.is_file()).unwrap_or_default() {
+            files.insert(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Verify that all declared outputs exist after the task has ran successfully,
+/// otherwise we would cache and hydrate an empty result.
+fn verify_task_outputs(
+    workspace: &Workspace,
+    task: &Task,
+    output_files: &[PathBuf],
+    snapshot: Option<HashSet<PathBuf>>,
+    current: Option<HashSet<PathBuf>>,
+) -> Result<(), WorkspaceError> {
+    let missing = |output: &str| {
+        WorkspaceError::TaskOutputMissing(
+            task.target.clone(),
+            output
+                .strip_prefix(&glob::normalize(&workspace.root).unwrap_or_default())
+                .unwrap_or(output)
+                .trim_start_matches('/')
+                .to_owned(),
+        )
+    };
+
+    for output_path in &task.output_paths {
+        if !output_path.exists() {
+            return Err(missing(&glob::normalize(output_path)?));
+        }
+    }
+
+    for output_glob in &task.output_globs {
+        if output_glob.starts_with('!') {
+            continue;
+        }
+
+        let patterns = vec![output_glob.clone()];
+        let globset = glob::GlobSet::new(&patterns).map_err(ProjectError::Glob)?;
+        let mut matched = false;
+
+        for file in output_files {
+            if globset.matches(file)? {
+                matched = true;
+                break;
+            }
+        }
+
+        if !matched {
+            return Err(missing(output_glob));
+        }
+    }
+
+    if let (Some(before), Some(after)) = (snapshot, current) {
+        let mut uncovered = after
+            .difference(&before)
+            .filter(|created| !output_files.iter().any(|file| file.starts_with(created)))
+            .map(|created| color::path(created))
+            .collect::<Vec<_>>();
+
+        if !uncovered.is_empty() {
+            uncovered.sort();
+
+            warn!(
+                target: LOG_TARGET,
+                "Target {} created files that are not declared as outputs, and will not be cached: {}",
+                color::target(&task.target),
+                uncovered.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn create_node_options(task: &Task) -> Vec<String> {
     string_vec![
         // "--inspect", // Enable node inspector
@@ -226,11 +316,69 @@ async fn create_target_command(
     Ok(command)
 }
 
+/// Gather values for the runtime token variables referenced by a task.
+/// The VCS is only queried when its variables are referenced.
+pub async fn create_token_runtime_data(
+    workspace: &Workspace,
+    vars: &BTreeSet<String>,
+) -> Result<TokenRuntimeData, WorkspaceError> {
+    let mut data = TokenRuntimeData {
+        branch: String::new(),
+        hash: String::new(),
+        now: Local::now(),
+    };
+
+    if vars.contains("branch") || vars.contains("hash") {
+        let vcs = workspace.detect_vcs()?;
+
+        data.branch = vcs.get_local_branch().await?;
+        data.hash = vcs.get_local_branch_revision().await?;
+    }
+
+    Ok(data)
+}
+
+/// Load the project and task for a target. When the target was inserted as a
+/// dependency with overrides, they are applied to a copy of the task.
+fn load_target_task(
+    workspace: &Workspace,
+    target_id: &str,
+    overrides: Option<&TaskDependency>,
+) -> Result<(Project, Task), WorkspaceError> {
+    let base_target_id = overrides
+        .map(|dep| dep.target.as_str())
+        .unwrap_or(target_id);
+    let (project_id, task_id) = Target::parse(base_target_id)?.ids()?;
+    let project = workspace.projects.load(&project_id)?;
+    let task = project.get_task(&task_id)?;
+    let task = match overrides {
+        Some(dep) => dep.apply_to(task),
+        None => task.clone(),
+    };
+
+    Ok((project, task))
+}
+
+/// Predict whether a target would be cached if it were ran, by comparing its
+/// current hash against the hash of its last run.
+pub async fn is_target_cached(
+    workspace: &Workspace,
+    target_id: &str,
+    overrides: Option<&TaskDependency>,
+) -> Result<bool, WorkspaceError> {
+    let cache = workspace.cache.cache_run_target_state(target_id).await?;
+    let (project, task) = load_target_task(workspace, target_id, overrides)?;
+    let hasher = create_target_hasher(workspace, &project, &task, &[]).await?;
+
+    Ok(cache.item.hash == hasher.to_hash())
+}
+
 pub async fn run_target(
     workspace: Arc<RwLock<Workspace>>,
     action: &mut Action,
     target_id: &str,
-    primary_target: &str,
+    overrides: Option<&TaskDependency>,
+    primary_targets: &HashSet<TargetID>,
     passthrough_args: &[String],
 ) -> Result<ActionStatus, WorkspaceError> {
     debug!(
@@ -243,10 +391,21 @@ pub async fn run_target(
     let mut cache = workspace.cache.cache_run_target_state(target_id).await?;
 
     // Gather the project and task
-    let is_primary = primary_target == target_id;
-    let (project_id, task_id) = Target::parse(target_id)?.ids()?;
-    let project = workspace.projects.load(&project_id)?;
-    let task = project.get_task(&task_id)?;
+    let is_primary = primary_targets.contains(target_id);
+    let (project, task) = load_target_task(&workspace, target_id, overrides)?;
+    let task = &task;
+
+    // Matrix tasks have nothing to run, as each variant ran as a dependency
+    if !task.variants.is_empty() {
+        debug!(
+            target: LOG_TARGET,
+            "Target {} is a matrix task, all {} variants have ran",
+            color::id(target_id),
+            task.variants.len()
+        );
+
+        return Ok(ActionStatus::Skipped);
+    }
 
     // Abort early if this build has already been cached/hashed
     let hasher = create_target_hasher(&workspace, &project, task, passthrough_args).await?;
@@ -274,9 +433,30 @@ pub async fn run_target(
 
         print_cache_item(&cache.item);
 
+        // Restore outputs that may have been removed since the last run
+        workspace
+            .cache
+            .hydrate_task_outputs(&hash, &workspace.root)
+            .await?;
+
         return Ok(ActionStatus::Cached);
     }
 
+    // Runtime token vars are resolved after hashing, so that volatile
+    // values (dates and times) don't bust the cache
+    let runtime_vars = task.get_runtime_vars();
+    let runtime_task;
+    let task = if runtime_vars.is_empty() {
+        task
+    } else {
+        let data = create_token_runtime_data(&workspace, &runtime_vars).await?;
+        let mut resolved_task = task.clone();
+
+        resolved_task.expand_runtime_vars(&data);
+        runtime_task = resolved_task;
+        &runtime_task
+    };
+
     // Build the command to run based on the task
     let mut command = create_target_command(&workspace, &project, task).await?;
     command.args(passthrough_args);
@@ -289,6 +469,14 @@ pub async fn run_target(
         command.inherit_colors();
     }
 
+    // Snapshot the project root so we can detect undeclared outputs
+    let has_outputs = !task.output_paths.is_empty() || !task.output_globs.is_empty();
+    let snapshot = if !has_outputs {
+        None
+    } else {
+        Some(snapshot_project_root(&project).await?)
+    };
+
     // Run the command as a child process and capture its output.
     // If the process fails and `retry_count` is greater than 0,
     // attempt the process again in case it passes.
@@ -308,7 +496,7 @@ pub async fn run_target(
             print_target_label(target_id, &attempt, attempt_total, Checkpoint::Pass);
             print_target_command(&workspace, &project, task, passthrough_args);
 
-            // If this target matches the primary target (the last task to run),
+            // If this target is a primary target (explicitly requested to run),
             // then we want to stream the output directly to the parent (inherit mode).
             command
                 .exec_stream_and_capture_output(if is_real_ci { Some(target_id) } else { None })
@@ -358,13 +546,26 @@ pub async fn run_target(
         }
     }
 
+    // Fail the action if outputs are missing, before we cache anything
+    let current = if snapshot.is_some() {
+        Some(snapshot_project_root(&project).await?)
+    } else {
+        None
+    };
+
+    // Globs are expanded at this point, as the files now exist
+    let output_files = task.expand_output_files(&workspace.root)?;
+
+    verify_task_outputs(&workspace, task, &output_files, snapshot, current)?;
+
     // Hard link outputs to the `.moon/cache/out` folder and to the cloud,
     // so that subsequent builds are faster, and any local outputs
-    // can be rehydrated easily.
-    for output_path in &task.output_paths {
+    // can be rehydrated easily. Only the matched files are linked,
+    // so that hydration restores the exact same set.
+    for output_file in &output_files {
         workspace
             .cache
-            .link_task_output_to_out(&hash, &project.root, output_path)
+            .link_task_output_to_out(&hash, &workspace.root, output_file)
             .await?;
     }
 
@@ -375,6 +576,7 @@ pub async fn run_target(
     // Write the cache with the result and output
     cache.item.exit_code = output.status.code().unwrap_or(0);
     cache.item.hash = hash;
+    cache.item.input_env_vars = task.get_input_env_vars();
     cache.item.last_run_time = cache.now_millis();
     cache.item.stderr = output_to_string(&output.stderr);
     cache.item.stdout = output_to_string(&output.stdout);
@@ -1,13 +1,17 @@
 use crate::errors::WorkspaceError;
+use moon_cache::CacheEngine;
+use moon_config::constants::{CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME};
 use moon_logger::{color, debug, trace, warn};
 use moon_project::{
-    ProjectGraph, ProjectID, Target, TargetError, TargetID, TargetProject, TouchedFilePaths,
+    find_shortest_cycle, format_dependency_origin, DependencySource, Project, ProjectGraph,
+    ProjectID, Target, TargetError, TargetID, TargetProject, TaskDependency, TouchedFilePaths,
 };
 use petgraph::algo::toposort;
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::DiGraph;
 use petgraph::Graph;
-use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub use petgraph::graph::NodeIndex;
 
@@ -21,6 +25,15 @@ pub enum Node {
 }
 
 impl Node {
+    pub fn get_type(&self) -> String {
+        match self {
+            Node::InstallNodeDeps => String::from("InstallNodeDeps"),
+            Node::RunTarget(_) => String::from("RunTarget"),
+            Node::SetupToolchain => String::from("SetupToolchain"),
+            Node::SyncProject(_) => String::from("SyncProject"),
+        }
+    }
+
     pub fn label(&self) -> String {
         match self {
             Node::InstallNodeDeps => String::from("InstallNodeDeps"),
@@ -31,6 +44,122 @@ impl Node {
     }
 }
 
+/// The predicted status of a node, if the graph were to be ran.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    Cached,
+    Run,
+    Skipped,
+}
+
+#[derive(Serialize)]
+struct NodeJson {
+    batch: usize,
+    id: usize,
+    label: String,
+    status: Option<NodeStatus>,
+    #[serde(rename = "type")]
+    type_of: String,
+}
+
+/// An edge from a node (source) to the node it depends on (target).
+#[derive(Serialize)]
+struct EdgeJson {
+    source: usize,
+    target: usize,
+}
+
+#[derive(Serialize)]
+struct GraphJson {
+    nodes: Vec<NodeJson>,
+    edges: Vec<EdgeJson>,
+}
+
+/// The reason a target was considered affected by touched files.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AffectedBy {
+    /// Workspace-level moon configuration files were touched.
+    Global,
+
+    /// An input of the target itself was touched.
+    Input(String),
+
+    /// An input of a task within an upstream project (via `dependsOn`) was touched.
+    Project(ProjectID, String),
+
+    /// An upstream target (via `deps`) was affected by a touched file.
+    Target(TargetID, String),
+}
+
+impl AffectedBy {
+    /// Return the touched file that caused the chain of affectedness.
+    pub fn get_source(&self) -> String {
+        match self {
+            AffectedBy::Global => String::from(".moon"),
+            AffectedBy::Input(source)
+            | AffectedBy::Project(_, source)
+            | AffectedBy::Target(_, source) => source.to_owned(),
+        }
+    }
+}
+
+/// Format where a task dependency was declared, for use in errors. Dependencies that
+/// are not declared in the project's `project.yml` were inherited from the global config.
+fn format_task_dependency_origin(project: &Project, task_id: &str, dep: &TaskDependency) -> String {
+    let dep_target = Target::parse(&dep.target).ok();
+
+    // Find the entry that expanded into this dependency
+    let local_entry = project
+        .config
+        .as_ref()
+        .and_then(|config| config.tasks.get(task_id))
+        .and_then(|task_config| task_config.deps.as_ref())
+        .and_then(|deps| {
+            deps.iter().find(|entry| {
+                let (entry_target, dep_target) = match (Target::parse(&entry.target), &dep_target) {
+                    (Ok(entry_target), Some(dep_target)) => (entry_target, dep_target),
+                    _ => return entry.target == dep.target,
+                };
+
+                if entry_target.task_id != dep_target.task_id {
+                    return false;
+                }
+
+                match &entry_target.project {
+                    TargetProject::Deps => dep_target.project_id.as_ref() != Some(&project.id),
+                    TargetProject::Own => dep_target.project_id.as_ref() == Some(&project.id),
+                    _ => entry.target == dep.target,
+                }
+            })
+        });
+
+    match local_entry {
+        Some(entry) => format!(
+            "<symbol>tasks.{}.deps</symbol> entry <target>{}</target> in <file>{}/{}</file>",
+            task_id, entry.target, project.source, CONFIG_PROJECT_FILENAME
+        ),
+        None => format!(
+            "<symbol>tasks.{}.deps</symbol> entry <target>{}</target> inherited from <file>{}/{}</file>",
+            task_id, dep.target, CONFIG_DIRNAME, CONFIG_PROJECT_FILENAME
+        ),
+    }
+}
+
+/// Return the dependency with its project alias (if used) replaced by the project ID,
+/// so that it's inserted as the same node as the canonical target.
+fn resolve_dependency(
+    dep: &TaskDependency,
+    projects: &ProjectGraph,
+) -> Result<TaskDependency, WorkspaceError> {
+    let target = projects.resolve_target(&Target::parse(&dep.target)?)?;
+
+    Ok(TaskDependency {
+        target: target.id,
+        ..dep.clone()
+    })
+}
+
 type GraphType = DiGraph<Node, ()>;
 type BatchedTopoSort = Vec<Vec<NodeIndex>>;
 
@@ -40,14 +169,43 @@ type BatchedTopoSort = Vec<Vec<NodeIndex>>;
 pub struct DepGraph {
     pub graph: GraphType,
 
+    /// Reasons for why each inserted target was affected by touched files.
+    pub affected_by: HashMap<TargetID, AffectedBy>,
+
+    /// Mapping of project and target IDs to their affected state.
+    affected_cache: HashMap<String, Option<AffectedBy>>,
+
+    /// Project and target IDs currently being walked for their affected state,
+    /// so that cycles do not recurse infinitely.
+    affected_visiting: HashSet<String>,
+
+    /// Cache engine for reading the input env vars recorded in each target's
+    /// last run, so that changed values mark the target as affected.
+    cache: Option<CacheEngine>,
+
+    /// Ordering-only (`after`) dependencies that are waiting for their
+    /// target to be inserted: the dependent node, target ID, and origin.
+    after_deps: Vec<(NodeIndex, TargetID, String)>,
+
+    /// Where each dependency edge was declared, for reporting cycles.
+    edge_origins: HashMap<(NodeIndex, NodeIndex), String>,
+
     /// Mapping of IDs to existing node indices.
     index_cache: HashMap<String, NodeIndex>,
 
     /// Reference node for the "install node deps" task.
     install_node_deps_index: NodeIndex,
 
+    /// Targets that were explicitly requested to run,
+    /// and not inserted as a dependency or dependent.
+    pub primary_targets: HashSet<TargetID>,
+
     /// Reference node for the "setup toolchain" task.
     setup_toolchain_index: NodeIndex,
+
+    /// Args and env vars to apply to targets that were inserted
+    /// as a dependency with overrides, keyed by their unique ID.
+    pub target_overrides: HashMap<TargetID, TaskDependency>,
 }
 
 impl DepGraph {
@@ -65,13 +223,32 @@ impl DepGraph {
         graph.add_edge(install_node_deps_index, setup_toolchain_index, ());
 
         DepGraph {
+            affected_by: HashMap::new(),
+            affected_cache: HashMap::new(),
+            affected_visiting: HashSet::new(),
+            after_deps: vec![],
+            cache: None,
+            edge_origins: HashMap::new(),
             graph,
             index_cache: HashMap::new(),
             install_node_deps_index,
+            primary_targets: HashSet::new(),
             setup_toolchain_index,
+            target_overrides: HashMap::new(),
         }
     }
 
+    /// Compare input env vars against the values recorded in the cache
+    /// when determining affected targets.
+    pub fn use_cache(&mut self, cache: &CacheEngine) {
+        self.cache = Some(cache.clone());
+    }
+
+    /// Return true if the target has been inserted into the graph.
+    pub fn has_target(&self, target_id: &str) -> bool {
+        self.index_cache.contains_key(target_id)
+    }
+
     pub fn get_node_from_index(&self, index: NodeIndex) -> Option<&Node> {
         self.graph.node_weight(index)
     }
@@ -80,8 +257,12 @@ impl DepGraph {
         let list = match toposort(&self.graph, None) {
             Ok(nodes) => nodes,
             Err(error) => {
+                // Sorting only fails because of a cycle, so report the full cycle
+                self.detect_cycle()?;
+
                 return Err(WorkspaceError::DepGraphCycleDetected(
                     self.get_node_from_index(error.node_id()).unwrap().label(),
+                    String::new(),
                 ));
             }
         };
@@ -152,43 +333,77 @@ impl DepGraph {
         projects: &ProjectGraph,
         touched_files: Option<&TouchedFilePaths>,
     ) -> Result<usize, WorkspaceError> {
-        let task_id = &target.task_id;
+        let target = &projects.resolve_target(target)?;
         let mut inserted_count = 0;
 
         match &target.project {
-            // :task
-            TargetProject::All => {
-                for project_id in projects.ids() {
-                    let project = projects.load(&project_id)?;
-
-                    if project.tasks.contains_key(task_id)
-                        && self
-                            .insert_target(&project_id, task_id, projects, touched_files)?
-                            .is_some()
-                    {
-                        inserted_count += 1;
-                    }
-                }
-            }
             // ^:task
             TargetProject::Deps => {
                 target.fail_with(TargetError::NoProjectDepsInRunContext)?;
             }
+            // ~:task
+            TargetProject::Own => {
+                target.fail_with(TargetError::NoProjectSelfInRunContext)?;
+            }
             // project:task
-            TargetProject::Id(project_id) => {
-                if self
-                    .insert_target(project_id, task_id, projects, touched_files)?
-                    .is_some()
-                {
+            TargetProject::Id(project_id) if !target.is_pattern() => {
+                if self.insert_primary_target(
+                    project_id,
+                    &target.task_id,
+                    projects,
+                    touched_files,
+                )? {
                     inserted_count += 1;
                 }
             }
-            // ~:task
-            TargetProject::Own => {
-                target.fail_with(TargetError::NoProjectSelfInRunContext)?;
+            // :task, *:task, project-*:task, #tag:task, project:*, project:task-*
+            _ => {
+                let project_ids = match &target.project {
+                    TargetProject::Id(project_id) => vec![project_id.to_owned()],
+                    _ => projects.ids(),
+                };
+                let mut matched_count = 0;
+
+                for project_id in project_ids {
+                    let project = projects.load(&project_id)?;
+
+                    if !target.matches_project(&project)? {
+                        continue;
+                    }
+
+                    let mut task_ids = vec![];
+
+                    for task_id in project.tasks.keys() {
+                        if target.matches_task(task_id)? {
+                            task_ids.push(task_id.to_owned());
+                        }
+                    }
+
+                    task_ids.sort();
+
+                    for task_id in task_ids {
+                        matched_count += 1;
+
+                        if self.insert_primary_target(
+                            &project_id,
+                            &task_id,
+                            projects,
+                            touched_files,
+                        )? {
+                            inserted_count += 1;
+                        }
+                    }
+                }
+
+                if matched_count == 0 && target.is_pattern() {
+                    target.fail_with(TargetError::NoMatchingTasks(target.id.clone()))?;
+                }
             }
         };
 
+        self.link_after_deps();
+        self.detect_cycle()?;
+
         Ok(inserted_count)
     }
 
@@ -197,6 +412,37 @@ impl DepGraph {
         target: &Target,
         projects: &ProjectGraph,
     ) -> Result<(), WorkspaceError> {
+        let target = &projects.resolve_target(target)?;
+
+        // :task already includes every project, so there are no more dependents to add
+        if matches!(target.project, TargetProject::All) {
+            return Ok(());
+        }
+
+        // Patterns were expanded when inserted, so add dependents for each match
+        if target.is_pattern() {
+            let mut matched_targets = vec![];
+
+            for primary_id in &self.primary_targets {
+                let primary = Target::parse(primary_id)?;
+                let (project_id, task_id) = primary.ids()?;
+
+                if target.matches_project(&projects.load(&project_id)?)?
+                    && target.matches_task(&task_id)?
+                {
+                    matched_targets.push(primary);
+                }
+            }
+
+            matched_targets.sort();
+
+            for matched_target in matched_targets {
+                self.run_target_dependents(&matched_target, projects)?;
+            }
+
+            return Ok(());
+        }
+
         trace!(
             target: TARGET,
             "Adding dependents to run for target {}",
@@ -211,10 +457,13 @@ impl DepGraph {
             let dependent = projects.load(&dependent_id)?;
 
             if dependent.tasks.contains_key(&task_id) {
-                self.run_target(&Target::new(&dependent_id, &task_id)?, projects, None)?;
+                self.insert_target(&dependent_id, &task_id, None, projects, None)?;
             }
         }
 
+        self.link_after_deps();
+        self.detect_cycle()?;
+
         Ok(())
     }
 
@@ -248,9 +497,21 @@ impl DepGraph {
         self.index_cache.insert(project_id.to_owned(), node_index);
 
         // But we need to wait on all dependent nodes
+        let dep_sources: HashMap<ProjectID, DependencySource> = projects
+            .get_dependency_sources_of(&project)?
+            .into_iter()
+            .collect();
+
         for dep_id in projects.get_dependencies_of(&project)? {
             let dep_node_index = self.sync_project(&dep_id, projects)?;
             self.graph.add_edge(node_index, dep_node_index, ());
+
+            if let Some(dep_source) = dep_sources.get(&dep_id) {
+                self.edge_origins.insert(
+                    (node_index, dep_node_index),
+                    format_dependency_origin(&project, dep_source),
+                );
+            }
         }
 
         Ok(node_index)
@@ -263,29 +524,327 @@ impl DepGraph {
         format!("{:?}", dot)
     }
 
+    /// Format as a DOT string, with nodes filled based on their predicted status.
+    pub fn to_dot_with_statuses(&self, statuses: &HashMap<NodeIndex, NodeStatus>) -> String {
+        let graph = self.graph.map(|_, n| n.label(), |_, e| e);
+        let dot = Dot::with_attr_getters(
+            &graph,
+            &[Config::EdgeNoLabel],
+            &|_, _| String::new(),
+            &|_, (index, _)| match statuses.get(&index) {
+                Some(NodeStatus::Cached) => String::from("style=filled, fillcolor=lightblue"),
+                Some(NodeStatus::Run) => String::from("style=filled, fillcolor=palegreen"),
+                Some(NodeStatus::Skipped) => {
+                    String::from("style=filled, fillcolor=gray, fontcolor=white")
+                }
+                None => String::new(),
+            },
+        );
+
+        format!("{:?}", dot)
+    }
+
+    /// Format as a JSON string, with each node's batch index and predicted status.
+    pub fn to_json(
+        &self,
+        statuses: &HashMap<NodeIndex, NodeStatus>,
+    ) -> Result<String, WorkspaceError> {
+        let mut batch_indices = HashMap::new();
+
+        for (batch, indices) in self.sort_batched_topological()?.into_iter().enumerate() {
+            for index in indices {
+                batch_indices.insert(index, batch);
+            }
+        }
+
+        let nodes = self
+            .graph
+            .node_indices()
+            .map(|index| {
+                let node = self.get_node_from_index(index).unwrap();
+
+                NodeJson {
+                    batch: *batch_indices.get(&index).unwrap_or(&0),
+                    id: index.index(),
+                    label: node.label(),
+                    status: statuses.get(&index).copied(),
+                    type_of: node.get_type(),
+                }
+            })
+            .collect();
+
+        let edges = self
+            .graph
+            .edge_indices()
+            .map(|index| {
+                let (source, target) = self.graph.edge_endpoints(index).unwrap();
+
+                EdgeJson {
+                    source: source.index(),
+                    target: target.index(),
+                }
+            })
+            .collect();
+
+        Ok(serde_json::to_string_pretty(&GraphJson { nodes, edges }).unwrap())
+    }
+
+    /// Link ordering-only (`after`) dependencies whose target now exists in the graph.
+    /// Targets that were never inserted are not ran, so remain pending.
+    fn link_after_deps(&mut self) {
+        let mut pending = vec![];
+
+        for (node, target_id, origin) in std::mem::take(&mut self.after_deps) {
+            match self.index_cache.get(&target_id) {
+                Some(dep_node) => {
+                    self.graph.update_edge(node, *dep_node, ());
+                    self.edge_origins.insert((node, *dep_node), origin);
+                }
+                None => {
+                    pending.push((node, target_id, origin));
+                }
+            };
+        }
+
+        self.after_deps = pending;
+    }
+
+    /// Fail with the shortest cycle found in the graph, including the
+    /// config file each dependency edge was declared in.
     fn detect_cycle(&self) -> Result<(), WorkspaceError> {
-        use petgraph::algo::kosaraju_scc;
+        let cycle = match find_shortest_cycle(&self.graph) {
+            Some(cycle) => cycle,
+            None => return Ok(()),
+        };
+
+        let label = |index: &NodeIndex| match self.get_node_from_index(*index).unwrap() {
+            Node::RunTarget(target_id) => target_id.to_owned(),
+            node => node.label(),
+        };
+
+        let origins = cycle
+            .windows(2)
+            .map(|pair| {
+                let mut origin = format!(
+                    "  <target>{}</target> → <target>{}</target>",
+                    label(&pair[0]),
+                    label(&pair[1])
+                );
+
+                if let Some(declared) = self.edge_origins.get(&(pair[0], pair[1])) {
+                    origin.push_str(&format!(" ({})", declared));
+                }
 
-        let scc = kosaraju_scc(&self.graph);
-        let cycle = scc
-            .last()
-            .unwrap()
-            .iter()
-            .map(|i| self.get_node_from_index(*i).unwrap().label())
-            .collect::<Vec<String>>()
-            .join(" → ");
+                origin
+            })
+            .collect::<Vec<String>>();
 
-        Err(WorkspaceError::DepGraphCycleDetected(cycle))
+        Err(WorkspaceError::DepGraphCycleDetected(
+            cycle.iter().map(label).collect::<Vec<String>>().join(" → "),
+            origins.join("\n"),
+        ))
+    }
+
+    /// Determine whether a target is affected by touched files, either directly through
+    /// its own inputs, or transitively through an upstream target or project.
+    fn get_affected_by(
+        &mut self,
+        project_id: &str,
+        task_id: &str,
+        projects: &ProjectGraph,
+        touched_files: &TouchedFilePaths,
+    ) -> Result<Option<AffectedBy>, WorkspaceError> {
+        let target_id = Target::format(project_id, task_id)?;
+
+        if let Some(affected_by) = self.affected_cache.get(&target_id) {
+            return Ok(affected_by.clone());
+        }
+
+        // Already being walked further up the chain
+        if !self.affected_visiting.insert(target_id.clone()) {
+            return Ok(None);
+        }
+
+        let project = projects.load(project_id)?;
+        let task = project.get_task(task_id)?;
+        let mut affected_by = None;
+
+        if projects.is_globally_affected(touched_files) {
+            affected_by = Some(AffectedBy::Global);
+        } else if let Some(source) = task.get_affected_by(
+            touched_files,
+            self.load_last_run_env_vars(&target_id)?.as_ref(),
+        )? {
+            affected_by = Some(AffectedBy::Input(source));
+        }
+
+        if affected_by.is_none() {
+            for dep in &task.deps {
+                let dep = &resolve_dependency(dep, projects)?;
+                let (dep_project_id, dep_task_id) = Target::parse(&dep.target)?.ids()?;
+
+                // Ordering-only and missing optional deps are never ran for this target
+                if dep.after
+                    || (dep.optional
+                        && !self.has_project_task(&dep_project_id, &dep_task_id, projects)?)
+                {
+                    continue;
+                }
+
+                if let Some(dep_affected_by) =
+                    self.get_affected_by(&dep_project_id, &dep_task_id, projects, touched_files)?
+                {
+                    affected_by = Some(AffectedBy::Target(
+                        dep.target.to_owned(),
+                        dep_affected_by.get_source(),
+                    ));
+
+                    break;
+                }
+            }
+        }
+
+        if affected_by.is_none() {
+            for dep_project_id in projects.get_dependencies_of(&project)? {
+                if let Some(dep_affected_by) =
+                    self.get_project_affected_by(&dep_project_id, projects, touched_files)?
+                {
+                    affected_by = Some(dep_affected_by);
+
+                    break;
+                }
+            }
+        }
+
+        self.affected_visiting.remove(&target_id);
+        self.affected_cache.insert(target_id, affected_by.clone());
+
+        Ok(affected_by)
+    }
+
+    /// Determine whether an input of any task within a project, or any project
+    /// it depends on, has been touched.
+    fn get_project_affected_by(
+        &mut self,
+        project_id: &str,
+        projects: &ProjectGraph,
+        touched_files: &TouchedFilePaths,
+    ) -> Result<Option<AffectedBy>, WorkspaceError> {
+        if let Some(affected_by) = self.affected_cache.get(project_id) {
+            return Ok(affected_by.clone());
+        }
+
+        // Already being walked further up the chain
+        if !self.affected_visiting.insert(project_id.to_owned()) {
+            return Ok(None);
+        }
+
+        let project = projects.load(project_id)?;
+        let mut affected_by = None;
+        let mut task_ids = project.tasks.keys().collect::<Vec<&String>>();
+
+        task_ids.sort();
+
+        for task_id in task_ids {
+            let last_run_env_vars =
+                self.load_last_run_env_vars(&Target::format(project_id, task_id)?)?;
+
+            if let Some(source) = project
+                .get_task(task_id)?
+                .get_affected_by(touched_files, last_run_env_vars.as_ref())?
+            {
+                affected_by = Some(AffectedBy::Project(project_id.to_owned(), source));
+
+                break;
+            }
+        }
+
+        if affected_by.is_none() {
+            for dep_project_id in projects.get_dependencies_of(&project)? {
+                if let Some(dep_affected_by) =
+                    self.get_project_affected_by(&dep_project_id, projects, touched_files)?
+                {
+                    affected_by = Some(dep_affected_by);
+
+                    break;
+                }
+            }
+        }
+
+        self.affected_visiting.remove(project_id);
+        self.affected_cache
+            .insert(project_id.to_owned(), affected_by.clone());
+
+        Ok(affected_by)
+    }
+
+    /// Load the input env var values recorded in the target's last run,
+    /// or `None` if no cache is available to compare against.
+    fn load_last_run_env_vars(
+        &self,
+        target_id: &str,
+    ) -> Result<Option<BTreeMap<String, String>>, WorkspaceError> {
+        match &self.cache {
+            Some(cache) => Ok(Some(cache.read_run_target_state(target_id)?.input_env_vars)),
+            None => Ok(None),
+        }
+    }
+
+    /// Return true if the project exists and has the task configured.
+    fn has_project_task(
+        &self,
+        project_id: &str,
+        task_id: &str,
+        projects: &ProjectGraph,
+    ) -> Result<bool, WorkspaceError> {
+        let project_id = projects.resolve_id(project_id)?;
+
+        if !projects.ids().contains(&project_id) {
+            return Ok(false);
+        }
+
+        Ok(projects.load(&project_id)?.tasks.contains_key(task_id))
+    }
+
+    fn insert_primary_target(
+        &mut self,
+        project_id: &str,
+        task_id: &str,
+        projects: &ProjectGraph,
+        touched_files: Option<&TouchedFilePaths>,
+    ) -> Result<bool, WorkspaceError> {
+        if self
+            .insert_target(project_id, task_id, None, projects, touched_files)?
+            .is_none()
+        {
+            return Ok(false);
+        }
+
+        self.primary_targets
+            .insert(Target::format(project_id, task_id)?);
+
+        // Variants run in place of the requested target, so are also primary
+        for variant_id in &projects.load(project_id)?.get_task(task_id)?.variants {
+            self.primary_targets
+                .insert(Target::format(project_id, variant_id)?);
+        }
+
+        Ok(true)
     }
 
     fn insert_target(
         &mut self,
         project_id: &str,
         task_id: &str,
+        dependency: Option<&TaskDependency>,
         projects: &ProjectGraph,
         touched_files: Option<&TouchedFilePaths>,
     ) -> Result<Option<NodeIndex>, WorkspaceError> {
-        let target_id = Target::format(project_id, task_id)?;
+        // Dependencies with overrides are inserted as their own node
+        let target_id = match dependency {
+            Some(dep) => dep.get_id(),
+            None => Target::format(project_id, task_id)?,
+        };
 
         if self.index_cache.contains_key(&target_id) {
             return Ok(Some(*self.index_cache.get(&target_id).unwrap()));
@@ -295,26 +854,35 @@ impl DepGraph {
 
         // Compare against touched files if provided
         if let Some(touched) = touched_files {
-            let globally_affected = projects.is_globally_affected(touched);
-
-            if globally_affected {
+            if projects.is_globally_affected(touched) {
                 warn!(
                     target: TARGET,
                     "Moon files touched, marking all targets as affected",
                 );
             }
 
-            // Validate task exists for project
-            if !globally_affected && !project.get_task(task_id)?.is_affected(touched)? {
-                trace!(
-                    target: TARGET,
-                    "Project {} task {} not affected based on touched files, skipping",
-                    color::id(project_id),
-                    color::id(task_id),
-                );
+            match self.get_affected_by(project_id, task_id, projects, touched)? {
+                Some(affected_by) => {
+                    trace!(
+                        target: TARGET,
+                        "Target {} affected by {}",
+                        color::target(&target_id),
+                        color::path(&affected_by.get_source()),
+                    );
 
-                return Ok(None);
-            }
+                    self.affected_by.insert(target_id.clone(), affected_by);
+                }
+                None => {
+                    trace!(
+                        target: TARGET,
+                        "Project {} task {} not affected based on touched files, skipping",
+                        color::id(project_id),
+                        color::id(task_id),
+                    );
+
+                    return Ok(None);
+                }
+            };
         }
 
         trace!(
@@ -333,15 +901,30 @@ impl DepGraph {
         // Also cache so we don't run the same target multiple times
         self.index_cache.insert(target_id.to_owned(), node);
 
+        if let Some(dep) = dependency {
+            if dep.has_overrides() {
+                self.target_overrides
+                    .insert(target_id.to_owned(), dep.clone());
+            }
+        }
+
         // And we also need to wait on all dependent nodes
         let task = project.get_task(task_id)?;
 
+        // Matrix tasks are ran through each of their variants
+        for variant_id in &task.variants {
+            if let Some(variant_node) =
+                self.insert_target(project_id, variant_id, None, projects, touched_files)?
+            {
+                self.graph.add_edge(node, variant_node, ());
+            }
+        }
+
         if !task.deps.is_empty() {
             let dep_names: Vec<String> = task
                 .deps
-                .clone()
-                .into_iter()
-                .map(|d| color::symbol(&d))
+                .iter()
+                .map(|d| color::symbol(&d.get_id()))
                 .collect();
 
             trace!(
@@ -351,16 +934,40 @@ impl DepGraph {
                 color::target(&target_id),
             );
 
-            for dep_target_id in &task.deps {
-                let dep_target = Target::parse(dep_target_id)?;
+            for declared_dep in &task.deps {
+                let origin = format_task_dependency_origin(&project, task_id, declared_dep);
+                let dep = &resolve_dependency(declared_dep, projects)?;
+                let dep_target = Target::parse(&dep.target)?;
+                let dep_project_id = dep_target.project_id.unwrap();
+
+                if dep.optional
+                    && !self.has_project_task(&dep_project_id, &dep_target.task_id, projects)?
+                {
+                    trace!(
+                        target: TARGET,
+                        "Optional dependency {} does not exist, skipping",
+                        color::target(&dep.target),
+                    );
+
+                    continue;
+                }
+
+                // Ordering-only deps are linked once their target has been inserted
+                if dep.after {
+                    self.after_deps.push((node, dep.get_id(), origin));
+
+                    continue;
+                }
 
                 if let Some(dep_node) = self.insert_target(
-                    &dep_target.project_id.unwrap(),
+                    &dep_project_id,
                     &dep_target.task_id,
+                    Some(dep),
                     projects,
                     touched_files,
                 )? {
                     self.graph.add_edge(node, dep_node, ());
+                    self.edge_origins.insert((node, dep_node), origin);
                 }
             }
         }
@@ -418,9 +1025,13 @@ mod tests {
                 ("build-c".to_owned(), "build-c".to_owned()),
                 ("chain".to_owned(), "chain".to_owned()),
                 ("cycle".to_owned(), "cycle".to_owned()),
+                ("depKinds".to_owned(), "dep-kinds".to_owned()),
+                ("depOverrides".to_owned(), "dep-overrides".to_owned()),
+                ("matrix".to_owned(), "matrix".to_owned()),
                 ("inputA".to_owned(), "input-a".to_owned()),
                 ("inputB".to_owned(), "input-b".to_owned()),
                 ("inputC".to_owned(), "input-c".to_owned()),
+                ("inputD".to_owned(), "input-d".to_owned()),
                 ("mergeAppend".to_owned(), "merge-append".to_owned()),
                 ("mergePrepend".to_owned(), "merge-prepend".to_owned()),
                 ("mergeReplace".to_owned(), "merge-replace".to_owned()),
@@ -461,9 +1072,7 @@ mod tests {
     }
 
     #[tokio::test]
-    #[should_panic(
-        expected = "CycleDetected(\"RunTarget(cycle:a) → RunTarget(cycle:b) → RunTarget(cycle:c)\")"
-    )]
+    #[should_panic(expected = "DepGraphCycleDetected(\"cycle:a → cycle:b → cycle:c → cycle:a\"")]
     async fn detects_cycles() {
         let projects = create_tasks_project_graph().await;
 
@@ -484,6 +1093,57 @@ mod tests {
         );
     }
 
+    mod task_dependency_origin {
+        use super::*;
+        use moon_config::{ProjectConfig, TaskConfig, TaskDependencyConfig};
+
+        fn create_project(deps: &[&str]) -> Project {
+            Project {
+                config: Some(ProjectConfig {
+                    tasks: HashMap::from([(
+                        "build".to_owned(),
+                        TaskConfig {
+                            deps: Some(
+                                deps.iter()
+                                    .map(|dep| TaskDependencyConfig::new(dep))
+                                    .collect(),
+                            ),
+                            ..TaskConfig::default()
+                        },
+                    )]),
+                    ..ProjectConfig::default()
+                }),
+                id: "app".to_owned(),
+                source: "apps/app".to_owned(),
+                ..Project::default()
+            }
+        }
+
+        #[test]
+        fn names_the_local_entry() {
+            let project = create_project(&["^:build", "~:lint"]);
+
+            assert_eq!(
+                format_task_dependency_origin(&project, "build", &TaskDependency::new("lib:build")),
+                "<symbol>tasks.build.deps</symbol> entry <target>^:build</target> in <file>apps/app/project.yml</file>"
+            );
+            assert_eq!(
+                format_task_dependency_origin(&project, "build", &TaskDependency::new("app:lint")),
+                "<symbol>tasks.build.deps</symbol> entry <target>~:lint</target> in <file>apps/app/project.yml</file>"
+            );
+        }
+
+        #[test]
+        fn reports_inherited_entries() {
+            let project = create_project(&["lib:build"]);
+
+            assert_eq!(
+                format_task_dependency_origin(&project, "build", &TaskDependency::new("app:lint")),
+                "<symbol>tasks.build.deps</symbol> entry <target>app:lint</target> inherited from <file>.moon/project.yml</file>"
+            );
+        }
+    }
+
     mod run_target {
         use super::*;
 
@@ -521,6 +1181,137 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn runs_all_matrix_variants() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("matrix", "unit").unwrap(), &projects, None)
+                .unwrap();
+
+            let base = *graph.index_cache.get("matrix:unit").unwrap();
+
+            for variant in [
+                "matrix:unit[env=node,shard=1]",
+                "matrix:unit[env=node,shard=2]",
+                "matrix:unit[env=jsdom,shard=1]",
+                "matrix:unit[env=jsdom,shard=2]",
+            ] {
+                let index = *graph.index_cache.get(variant).unwrap();
+
+                assert!(graph.graph.contains_edge(base, index));
+                assert!(graph.primary_targets.contains(variant));
+            }
+        }
+
+        #[tokio::test]
+        async fn runs_a_single_matrix_variant() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(
+                    &Target::parse("matrix:unit[env=node,shard=2]").unwrap(),
+                    &projects,
+                    None,
+                )
+                .unwrap();
+
+            assert!(graph.has_target("matrix:unit[env=node,shard=2]"));
+            assert!(!graph.has_target("matrix:unit"));
+            assert!(!graph.has_target("matrix:unit[env=node,shard=1]"));
+        }
+
+        #[tokio::test]
+        async fn skips_missing_optional_deps() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("depKinds", "check").unwrap(), &projects, None)
+                .unwrap();
+
+            assert!(graph.has_target("depKinds:check"));
+            assert!(graph.has_target("depKinds:format"));
+            assert!(!graph.has_target("depKinds:missing"));
+            assert!(!graph.has_target("unknown:task"));
+        }
+
+        #[tokio::test]
+        async fn doesnt_insert_after_deps() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("depKinds", "check").unwrap(), &projects, None)
+                .unwrap();
+
+            assert!(!graph.has_target("depKinds:lint"));
+        }
+
+        #[tokio::test]
+        async fn orders_after_deps_when_both_are_inserted() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("depKinds", "check").unwrap(), &projects, None)
+                .unwrap();
+            graph
+                .run_target(&Target::new("depKinds", "lint").unwrap(), &projects, None)
+                .unwrap();
+
+            let check = *graph.index_cache.get("depKinds:check").unwrap();
+            let lint = *graph.index_cache.get("depKinds:lint").unwrap();
+
+            assert!(graph.graph.contains_edge(check, lint));
+            assert!(graph.after_deps.is_empty());
+        }
+
+        #[tokio::test]
+        async fn inserts_a_node_per_dependency_override() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(
+                    &Target::new("depOverrides", "release").unwrap(),
+                    &projects,
+                    None,
+                )
+                .unwrap();
+            graph
+                .run_target(
+                    &Target::new("depOverrides", "verify").unwrap(),
+                    &projects,
+                    None,
+                )
+                .unwrap();
+
+            let mut target_ids = graph
+                .graph
+                .node_indices()
+                .filter_map(|index| match graph.get_node_from_index(index).unwrap() {
+                    Node::RunTarget(target_id) => Some(target_id.to_owned()),
+                    _ => None,
+                })
+                .collect::<Vec<String>>();
+
+            target_ids.sort();
+
+            // release, verify, and 3 distinct codegen combinations
+            assert_eq!(target_ids.len(), 5);
+            assert!(graph.has_target("depOverrides:codegen"));
+            assert_eq!(graph.target_overrides.len(), 2);
+
+            for (target_id, dep) in &graph.target_overrides {
+                assert!(target_id.starts_with("depOverrides:codegen@"));
+                assert_eq!(dep.target, "depOverrides:codegen");
+                assert!(graph.has_target(target_id));
+            }
+        }
+
         #[tokio::test]
         async fn deps_chain_target() {
             let projects = create_tasks_project_graph().await;
@@ -606,6 +1397,37 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn tracks_primary_targets() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("chain", "a").unwrap(), &projects, None)
+                .unwrap();
+            graph
+                .run_target(&Target::parse(":build").unwrap(), &projects, None)
+                .unwrap();
+
+            let mut primary = graph
+                .primary_targets
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>();
+            primary.sort();
+
+            assert_eq!(
+                primary,
+                vec![
+                    "basic:build".to_owned(),
+                    "build-a:build".to_owned(),
+                    "build-b:build".to_owned(),
+                    "build-c:build".to_owned(),
+                    "chain:a".to_owned(),
+                ]
+            );
+        }
+
         #[tokio::test]
         async fn runs_all_projects_for_target_all_scope() {
             let projects = create_tasks_project_graph().await;
@@ -648,6 +1470,120 @@ mod tests {
             );
         }
 
+        #[tokio::test]
+        async fn expands_project_patterns() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(&Target::parse("build-*:build").unwrap(), &projects, None)
+                .unwrap();
+
+            let mut primary = graph
+                .primary_targets
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>();
+            primary.sort();
+
+            assert_eq!(inserted_count, 3);
+            assert_eq!(
+                primary,
+                vec![
+                    "build-a:build".to_owned(),
+                    "build-b:build".to_owned(),
+                    "build-c:build".to_owned(),
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn expands_task_patterns() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(&Target::parse("basic:*").unwrap(), &projects, None)
+                .unwrap();
+
+            let mut primary = graph
+                .primary_targets
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>();
+            primary.sort();
+
+            assert_eq!(inserted_count, 3);
+            assert_eq!(
+                primary,
+                vec![
+                    "basic:build".to_owned(),
+                    "basic:lint".to_owned(),
+                    "basic:test".to_owned(),
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn expands_project_and_task_patterns() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(&Target::parse("*:b*").unwrap(), &projects, None)
+                .unwrap();
+
+            assert!(inserted_count > 0);
+            assert!(graph.primary_targets.contains("basic:build"));
+            assert!(graph.primary_targets.contains("build-a:build"));
+            assert!(!graph.primary_targets.contains("basic:lint"));
+        }
+
+        #[tokio::test]
+        async fn expands_project_tags() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(&Target::parse("#frontend:build").unwrap(), &projects, None)
+                .unwrap();
+
+            let mut primary = graph
+                .primary_targets
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>();
+            primary.sort();
+
+            assert_eq!(inserted_count, 2);
+            assert_eq!(
+                primary,
+                vec!["build-a:build".to_owned(), "build-c:build".to_owned()]
+            );
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "Project(Target(NoMatchingTasks(\"#unknown:build\")))")]
+        async fn errors_for_tag_without_matches() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::parse("#unknown:build").unwrap(), &projects, None)
+                .unwrap();
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "Project(Target(NoMatchingTasks(\"*:unknown-*\")))")]
+        async fn errors_for_pattern_without_matches() {
+            let projects = create_tasks_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::parse("*:unknown-*").unwrap(), &projects, None)
+                .unwrap();
+        }
+
         #[tokio::test]
         #[should_panic(expected = "Project(Target(NoProjectDepsInRunContext))")]
         async fn errors_for_target_deps_scope() {
@@ -761,6 +1697,158 @@ mod tests {
 
             assert_snapshot!(graph.to_dot());
         }
+
+        #[tokio::test]
+        async fn affected_by_upstream_target() {
+            let projects = create_tasks_project_graph().await;
+            let touched_file = get_fixtures_dir("tasks").join("input-a/a.ts");
+            let touched_files = HashSet::from([touched_file.clone()]);
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(
+                    &Target::new("inputD", "d").unwrap(),
+                    &projects,
+                    Some(&touched_files),
+                )
+                .unwrap();
+
+            assert_eq!(inserted_count, 1);
+            assert_eq!(
+                graph.affected_by.get("inputD:d").unwrap(),
+                &AffectedBy::Target(
+                    "inputA:a".to_owned(),
+                    touched_file.to_string_lossy().to_string()
+                )
+            );
+            assert_eq!(
+                graph.affected_by.get("inputA:a").unwrap(),
+                &AffectedBy::Input(touched_file.to_string_lossy().to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn affected_by_upstream_project() {
+            let projects = create_tasks_project_graph().await;
+            let touched_file = get_fixtures_dir("tasks").join("input-a/a2.ts");
+            let touched_files = HashSet::from([touched_file.clone()]);
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(
+                    &Target::new("inputD", "d2").unwrap(),
+                    &projects,
+                    Some(&touched_files),
+                )
+                .unwrap();
+
+            assert_eq!(inserted_count, 1);
+            assert_eq!(
+                graph.affected_by.get("inputD:d2").unwrap(),
+                &AffectedBy::Project(
+                    "inputA".to_owned(),
+                    touched_file.to_string_lossy().to_string()
+                )
+            );
+        }
+
+        #[tokio::test]
+        async fn skips_if_upstream_project_file_is_not_an_input() {
+            let projects = create_tasks_project_graph().await;
+            let touched_files =
+                HashSet::from([get_fixtures_dir("tasks").join("input-a/README.md")]);
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(
+                    &Target::new("inputD", "d2").unwrap(),
+                    &projects,
+                    Some(&touched_files),
+                )
+                .unwrap();
+
+            assert_eq!(inserted_count, 0);
+            assert!(graph.affected_by.is_empty());
+        }
+
+        #[tokio::test]
+        async fn skips_if_nothing_upstream_touched() {
+            let projects = create_tasks_project_graph().await;
+            let touched_files = HashSet::from([get_fixtures_dir("tasks").join("input-b/b.ts")]);
+
+            let mut graph = DepGraph::default();
+            let inserted_count = graph
+                .run_target(
+                    &Target::new("inputD", "d").unwrap(),
+                    &projects,
+                    Some(&touched_files),
+                )
+                .unwrap();
+
+            assert_eq!(inserted_count, 0);
+            assert!(graph.affected_by.is_empty());
+        }
+    }
+
+    mod to_json {
+        use super::*;
+
+        #[tokio::test]
+        async fn includes_nodes_edges_and_batches() {
+            let projects = create_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("tasks", "lint").unwrap(), &projects, None)
+                .unwrap();
+
+            let statuses = HashMap::from([(NodeIndex::new(3), NodeStatus::Cached)]);
+            let json: serde_json::Value =
+                serde_json::from_str(&graph.to_json(&statuses).unwrap()).unwrap();
+
+            assert_eq!(json["nodes"].as_array().unwrap().len(), 4);
+            assert_eq!(json["edges"].as_array().unwrap().len(), 4);
+            assert_eq!(json["nodes"][0]["type"], "SetupToolchain");
+            assert_eq!(json["nodes"][0]["batch"], 0);
+            assert_eq!(json["nodes"][0]["status"], serde_json::Value::Null);
+            assert_eq!(json["nodes"][3]["label"], "RunTarget(tasks:lint)");
+            assert_eq!(json["nodes"][3]["type"], "RunTarget");
+            assert_eq!(json["nodes"][3]["batch"], 2);
+            assert_eq!(json["nodes"][3]["status"], "cached");
+        }
+    }
+
+    mod to_dot_with_statuses {
+        use super::*;
+
+        #[tokio::test]
+        async fn fills_nodes_by_status() {
+            let projects = create_project_graph().await;
+
+            let mut graph = DepGraph::default();
+            graph
+                .run_target(&Target::new("tasks", "lint").unwrap(), &projects, None)
+                .unwrap();
+
+            let statuses = HashMap::from([
+                (NodeIndex::new(2), NodeStatus::Run),
+                (NodeIndex::new(3), NodeStatus::Skipped),
+            ]);
+            let dot = graph.to_dot_with_statuses(&statuses);
+
+            let sync_line = dot
+                .lines()
+                .find(|line| line.contains("SyncProject(tasks)"))
+                .unwrap();
+            let run_line = dot
+                .lines()
+                .find(|line| line.contains("RunTarget(tasks:lint)"))
+                .unwrap();
+
+            assert!(sync_line.contains("style=filled, fillcolor=palegreen"));
+            assert!(run_line.contains("style=filled, fillcolor=gray, fontcolor=white"));
+            assert!(!dot.contains("fillcolor=lightblue"));
+        }
     }
 
     mod sync_project {
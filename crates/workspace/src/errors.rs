@@ -7,8 +7,8 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
-    #[error("A dependency cycle has been detected for <file>{0}</file>.")]
-    DepGraphCycleDetected(String),
+    #[error("A dependency cycle has been detected between targets <target>{0}</target>.\n\n{1}")]
+    DepGraphCycleDetected(String, String), // cycle, edge origins
 
     #[error("Unknown node {0} found in dependency graph. How did this get here?")]
     DepGraphUnknownNode(usize),
@@ -16,6 +16,11 @@ pub enum WorkspaceError {
     #[error("{0}")]
     ActionRunnerFailure(String),
 
+    #[error(
+        "Target <target>{0}</target> ran successfully, but its declared output <path>{1}</path> does not exist."
+    )]
+    TaskOutputMissing(String, String),
+
     #[error(
         "Unable to determine workspace root. Please create a <file>{}</file> configuration folder.",
         constants::CONFIG_DIRNAME
@@ -49,6 +54,12 @@ pub enum WorkspaceError {
     )]
     InvalidGlobalProjectConfigFile(String),
 
+    #[error(
+        "Failed to validate <file>{}/{0}</file> configuration file.\n\n{1}",
+        constants::CONFIG_DIRNAME
+    )]
+    InvalidScopedProjectConfigFile(String, String), // file name, errors
+
     #[error(transparent)]
     Ignore(#[from] IgnoreError),
 
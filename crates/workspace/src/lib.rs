@@ -8,7 +8,8 @@ mod workspace;
 
 pub use action::{Action, ActionStatus};
 pub use action_runner::ActionRunner;
-pub use dep_graph::DepGraph;
+pub use actions::is_target_cached;
+pub use dep_graph::{AffectedBy, DepGraph, Node, NodeStatus};
 pub use errors::WorkspaceError;
 pub use vcs::TouchedFiles;
 pub use workspace::Workspace;
@@ -3,7 +3,9 @@ use crate::vcs::{Vcs, VcsManager};
 use moon_cache::CacheEngine;
 use moon_config::package::PackageJson;
 use moon_config::tsconfig::TsConfigJson;
-use moon_config::{constants, format_errors, GlobalProjectConfig, WorkspaceConfig};
+use moon_config::{
+    constants, format_errors, GlobalProjectConfig, ProjectLanguage, ProjectType, WorkspaceConfig,
+};
 use moon_logger::{color, debug, trace};
 use moon_project::ProjectGraph;
 use moon_toolchain::Toolchain;
@@ -43,13 +45,64 @@ fn load_global_project_config(root_dir: &Path) -> Result<GlobalProjectConfig, Wo
         color::path(root_dir)
     );
 
+    let mut config = if config_path.exists() {
+        match GlobalProjectConfig::load(config_path) {
+            Ok(cfg) => cfg,
+            Err(errors) => {
+                return Err(WorkspaceError::InvalidGlobalProjectConfigFile(
+                    format_errors(&errors, "  "),
+                ))
+            }
+        }
+    } else {
+        GlobalProjectConfig::default()
+    };
+
+    for (language, scope) in [
+        (ProjectLanguage::JavaScript, "javascript"),
+        (ProjectLanguage::TypeScript, "typescript"),
+    ] {
+        if let Some(scoped_config) = load_scoped_global_project_config(root_dir, scope)? {
+            config.languages.insert(language, scoped_config);
+        }
+    }
+
+    for (type_of, scope) in [
+        (ProjectType::Application, "application"),
+        (ProjectType::Library, "library"),
+        (ProjectType::Tool, "tool"),
+    ] {
+        if let Some(scoped_config) = load_scoped_global_project_config(root_dir, scope)? {
+            config.types.insert(type_of, scoped_config);
+        }
+    }
+
+    Ok(config)
+}
+
+// project.<scope>.yml
+fn load_scoped_global_project_config(
+    root_dir: &Path,
+    scope: &str,
+) -> Result<Option<GlobalProjectConfig>, WorkspaceError> {
+    let file_name = format!("project.{}.yml", scope);
+    let config_path = root_dir.join(constants::CONFIG_DIRNAME).join(&file_name);
+
     if !config_path.exists() {
-        return Ok(GlobalProjectConfig::default());
+        return Ok(None);
     }
 
+    trace!(
+        target: LOG_TARGET,
+        "Loading scoped {} from {}",
+        color::file(&format!("{}/{}", constants::CONFIG_DIRNAME, file_name)),
+        color::path(root_dir)
+    );
+
     match GlobalProjectConfig::load(config_path) {
-        Ok(cfg) => Ok(cfg),
-        Err(errors) => Err(WorkspaceError::InvalidGlobalProjectConfigFile(
+        Ok(cfg) => Ok(Some(cfg)),
+        Err(errors) => Err(WorkspaceError::InvalidScopedProjectConfigFile(
+            file_name,
             format_errors(&errors, "  "),
         )),
     }
@@ -176,9 +229,19 @@ impl Workspace {
         // Setup components
         let cache = CacheEngine::create(&root_dir).await?;
         let toolchain = Toolchain::create(&root_dir, &config).await?;
-        let projects =
+        let mut projects =
             ProjectGraph::create(&root_dir, project_config, &config.projects, &cache).await?;
 
+        projects
+            .set_infer_dependencies(config.node.infer_project_dependencies)
+            .set_infer_tsconfig_dependencies(
+                config
+                    .typescript
+                    .infer_project_dependencies
+                    .then(|| config.typescript.project_config_file_name.clone()),
+            )
+            .set_constraints(config.constraints.clone());
+
         Ok(Workspace {
             cache,
             config,
@@ -196,3 +259,115 @@ impl Workspace {
         VcsManager::load(&self.config, &self.working_dir)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moon_utils::test::get_fixtures_dir;
+
+    fn get_task_command(config: &GlobalProjectConfig, task_id: &str) -> String {
+        config.tasks.get(task_id).unwrap().command.clone().unwrap()
+    }
+
+    mod load_scoped_global_project_config {
+        use super::*;
+
+        #[test]
+        fn returns_none_if_missing() {
+            let config = load_scoped_global_project_config(
+                &get_fixtures_dir("scoped-project-configs"),
+                "typescript",
+            )
+            .unwrap();
+
+            assert!(config.is_none());
+        }
+
+        #[test]
+        fn loads_by_scope() {
+            let config = load_scoped_global_project_config(
+                &get_fixtures_dir("scoped-project-configs"),
+                "javascript",
+            )
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(get_task_command(&config, "build"), "babel");
+            assert_eq!(get_task_command(&config, "test"), "mocha");
+        }
+
+        #[test]
+        fn errors_for_invalid_config() {
+            let error = load_scoped_global_project_config(
+                &get_fixtures_dir("scoped-project-configs-invalid"),
+                "tool",
+            )
+            .unwrap_err();
+
+            assert!(matches!(
+                error,
+                WorkspaceError::InvalidScopedProjectConfigFile(file_name, _) if file_name == "project.tool.yml"
+            ));
+        }
+    }
+
+    mod load_global_project_config {
+        use super::*;
+
+        #[test]
+        fn loads_scopes_by_language_and_type() {
+            let config =
+                load_global_project_config(&get_fixtures_dir("scoped-project-configs")).unwrap();
+
+            assert_eq!(
+                config.languages.keys().collect::<Vec<_>>(),
+                vec![&ProjectLanguage::JavaScript]
+            );
+            assert_eq!(
+                config.types.keys().collect::<Vec<_>>(),
+                vec![&ProjectType::Application]
+            );
+        }
+
+        #[test]
+        fn merges_scopes_with_root_config() {
+            let config =
+                load_global_project_config(&get_fixtures_dir("scoped-project-configs")).unwrap();
+            let scoped = config.scoped_to(&ProjectLanguage::JavaScript, &ProjectType::Application);
+
+            let mut task_ids = scoped.tasks.keys().collect::<Vec<_>>();
+            task_ids.sort();
+
+            assert_eq!(task_ids, vec!["build", "lint", "serve", "test"]);
+            assert_eq!(get_task_command(&scoped, "lint"), "eslint");
+            // Type takes precedence over language, which takes precedence over the root
+            assert_eq!(get_task_command(&scoped, "test"), "vitest");
+
+            let mut file_groups = scoped.file_groups.keys().collect::<Vec<_>>();
+            file_groups.sort();
+
+            assert_eq!(file_groups, vec!["assets", "sources"]);
+        }
+
+        #[test]
+        fn only_merges_matching_scopes() {
+            let config =
+                load_global_project_config(&get_fixtures_dir("scoped-project-configs")).unwrap();
+            let scoped = config.scoped_to(&ProjectLanguage::TypeScript, &ProjectType::Library);
+
+            let mut task_ids = scoped.tasks.keys().collect::<Vec<_>>();
+            task_ids.sort();
+
+            assert_eq!(task_ids, vec!["lint", "test"]);
+            assert_eq!(get_task_command(&scoped, "test"), "jest");
+        }
+
+        #[test]
+        fn bubbles_up_invalid_scoped_config() {
+            assert!(load_global_project_config(&get_fixtures_dir(
+                "scoped-project-configs-invalid"
+            ))
+            .is_err());
+        }
+    }
+}
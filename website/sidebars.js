@@ -82,10 +82,13 @@ const sidebars = {
 			items: [
 				'commands/overview',
 				'commands/bin',
+				'commands/check',
 				'commands/ci',
+				'commands/dep-graph',
 				'commands/init',
 				'commands/project',
 				'commands/project-graph',
+				'commands/query',
 				'commands/run',
 				'commands/setup',
 				'commands/teardown',